@@ -1,58 +1,122 @@
+use crate::asteroids::fragmentation::{fragment, spawn_fragments, Fragment, Shattered};
+use crate::asteroids::messages::{AsteroidBounce, AsteroidDestroyed, ShipImpact, SunContact};
 use crate::asteroids::{utils::f, *};
 use crate::controller::structs::Player;
-use crate::game_over::GameOverState;
-use crate::globals_structs::{MusicVolume, Score};
-use crate::physics::{RotationVelocity, Velocity};
-use crate::player::{Ammo, PlayerHitBox, PLAYER_MASS};
-use crate::spritesheet::{AnimationDuration, AnimationTimer};
-
-use bevy::audio::Volume;
-use rand::seq::IndexedRandom;
-use rand::Rng;
+use crate::physics::{
+    sphere_inertia, sweep_sphere, PhysicsMaterial, PhysicsState, RotationVelocity, Velocity,
+};
+use crate::player::{Ammo, PlayerHitBox, PLAYER_MASS, PLAYER_RADIUS};
 
 struct CollisionBody {
     tr: Vec3,
+    // center of mass, differs from `tr` for the ship hitboxes
+    center: Vec3,
     vel: Vec3,
+    spin: Vec3,
     radius: f32,
     mass: f32,
+    inertia: f32,
 }
 
-fn mass_collision(a_body: &mut CollisionBody, b_body: &mut CollisionBody, dist: f32) {
+/// Collision between two spheres, the contact point is on the surface of `a_body`.
+/// Off-center and grazing hits (through friction) change the spin of both bodies.
+/// `material` is the combined one of the pair. Returns the impulse received by `a_body`
+fn mass_collision(
+    a_body: &mut CollisionBody,
+    b_body: &mut CollisionBody,
+    dist: f32,
+    material: PhysicsMaterial,
+) -> Vec3 {
     let delta = b_body.tr - a_body.tr;
     let n = delta / dist;
 
     let overlap = (a_body.radius + b_body.radius) - dist;
     let correction = n * (overlap / (a_body.mass + b_body.mass));
     a_body.tr -= correction * b_body.mass; // lighter one moves more
+    a_body.center -= correction * b_body.mass;
     b_body.tr += correction * a_body.mass;
+    b_body.center += correction * a_body.mass;
+
+    let contact = a_body.tr + n * a_body.radius;
+    let ra = contact - a_body.center;
+    let rb = contact - b_body.center;
 
-    let v_rel = a_body.vel - b_body.vel;
+    let v_rel = (a_body.vel + a_body.spin.cross(ra)) - (b_body.vel + b_body.spin.cross(rb));
     let vel_along_normal = v_rel.dot(n);
+    if vel_along_normal <= 0.0 {
+        return Vec3::ZERO; // already separating
+    }
 
-    let impulse_mag = -(2.0 * vel_along_normal) / (1.0 / a_body.mass + 1.0 / b_body.mass);
-    let impulse = impulse_mag * n;
+    // inverse of the effective mass along `axis` at the contact point
+    let effective = |axis: Vec3| {
+        1.0 / a_body.mass
+            + 1.0 / b_body.mass
+            + ra.cross(axis).length_squared() / a_body.inertia
+            + rb.cross(axis).length_squared() / b_body.inertia
+    };
+
+    let normal_mag = ((1.0 + material.restitution) * vel_along_normal) / effective(n);
+    let mut impulse = -normal_mag * n;
+
+    let tangent_vel = v_rel - n * vel_along_normal;
+    if tangent_vel.length_squared() > f32::EPSILON {
+        let t = tangent_vel.normalize();
+        let friction_mag =
+            (tangent_vel.length() / effective(t)).min(material.friction * normal_mag);
+        impulse -= friction_mag * t;
+    }
 
     a_body.vel += impulse / a_body.mass;
     b_body.vel -= impulse / b_body.mass;
+    a_body.spin += ra.cross(impulse) / a_body.inertia;
+    b_body.spin -= rb.cross(impulse) / b_body.inertia;
+
+    impulse
 }
 
+/// Bounces touching asteroids. Violent impacts shatter the smaller one, slow
+/// touches between small rocks merge them into a bigger one
 pub fn asteroid_asteroid_collision(
-    mut asteroids_query: Query<(Entity, &Asteroid, &mut Transform, &mut Velocity)>,
+    mut commands: Commands,
+    gameconfig: Res<GameConfig>,
+    assets: Res<AsteroidAssets>,
+    mut bounces: MessageWriter<AsteroidBounce>,
+    mut destroyed_writer: MessageWriter<AsteroidDestroyed>,
+    mut asteroids_query: Query<
+        (
+            Entity,
+            &Asteroid,
+            &mut Transform,
+            &mut Velocity,
+            &mut RotationVelocity,
+            Option<&PhysicsMaterial>,
+            Option<&Shattered>,
+        ),
+        Without<DespawnAnimation>,
+    >,
 ) {
+    let mut rng = rand::rng();
+    let config = &gameconfig.asteroids.impacts;
+    let default_material = PhysicsMaterial::from(&gameconfig.physics.material);
     let entity_ids: Vec<Entity> = asteroids_query
         .iter()
-        .map(|(e, _, _, _)| e.entity())
+        .map(|(e, _, _, _, _, _, _)| e.entity())
         .collect(); // just to get count
     let len = entity_ids.len();
+    // shattered or merged during this tick
+    let mut broken: Vec<Entity> = Vec::new();
 
     for i in 0..len {
         for j in (i + 1)..len {
+            if broken.contains(&entity_ids[i]) || broken.contains(&entity_ids[j]) {
+                continue;
+            }
             let [a, b] = asteroids_query
                 .get_many_mut([entity_ids[i], entity_ids[j]])
                 .unwrap();
 
-            let (_, a_ast, mut a_tf, mut a_vel) = a;
-            let (_, b_ast, mut b_tf, mut b_vel) = b;
+            let (a_entity, a_ast, mut a_tf, mut a_vel, mut a_rot, a_material, a_shattered) = a;
+            let (b_entity, b_ast, mut b_tf, mut b_vel, mut b_rot, b_material, b_shattered) = b;
             let dist = a_tf.translation.distance(b_tf.translation);
 
             let a_radius = a_ast.size;
@@ -60,266 +124,437 @@ pub fn asteroid_asteroid_collision(
             if dist > a_radius + b_radius {
                 continue;
             }
+            let a_mass = a_ast.size.powi(3);
+            let b_mass = b_ast.size.powi(3);
             let a_body = &mut CollisionBody {
                 tr: a_tf.translation,
+                center: a_tf.translation,
                 vel: a_vel.0,
+                spin: a_rot.0,
                 radius: a_radius,
-                mass: a_ast.size.powi(3),
+                mass: a_mass,
+                inertia: sphere_inertia(a_mass, a_radius),
             };
             let b_body = &mut CollisionBody {
                 tr: b_tf.translation,
+                center: b_tf.translation,
                 vel: b_vel.0,
+                spin: b_rot.0,
                 radius: b_radius,
-                mass: b_ast.size.powi(3),
+                mass: b_mass,
+                inertia: sphere_inertia(b_mass, b_radius),
             };
 
-            mass_collision(a_body, b_body, dist);
+            let v_rel = a_body.vel - b_body.vel;
+            let approach = v_rel.dot((b_body.tr - a_body.tr).normalize_or_zero());
+
+            // fresh fragments fly apart slowly, they must not stick back together
+            let settled = a_shattered.is_none() && b_shattered.is_none();
+            if settled
+                && approach > 0.0
+                && v_rel.length() < config.merge_speed
+                && a_radius.max(b_radius) <= config.merge_max_size
+            {
+                broken.extend([a_entity, b_entity]);
+                commands.entity(a_entity).despawn();
+                commands.entity(b_entity).despawn();
+                let (center, merged) = merge(a_body, b_body);
+                spawn_fragments(
+                    &mut commands,
+                    &assets,
+                    &gameconfig,
+                    center,
+                    &[merged],
+                    false,
+                    &mut rng,
+                );
+                continue;
+            }
+
+            let before = [(a_body.vel, a_body.spin), (b_body.vel, b_body.spin)];
+            let material = a_material
+                .unwrap_or(&default_material)
+                .combine(b_material.unwrap_or(&default_material));
+            let impulse = mass_collision(a_body, b_body, dist, material);
+            let contact = a_body.tr + (b_body.tr - a_body.tr).normalize_or_zero() * a_radius;
+            bounces.write(AsteroidBounce {
+                a: a_entity,
+                b: b_entity,
+                position: contact,
+                impulse,
+            });
 
             a_tf.translation = a_body.tr;
             b_tf.translation = b_body.tr;
 
             a_vel.0 = a_body.vel;
             b_vel.0 = b_body.vel;
+            a_rot.0 = a_body.spin;
+            b_rot.0 = b_body.spin;
+
+            // kinetic energy of the impact in the center of mass frame, per unit of mass of
+            // the smaller body so the threshold holds for every size
+            let reduced_mass = a_mass * b_mass / (a_mass + b_mass);
+            let energy = 0.5 * reduced_mass * approach.max(0.0).powi(2) / a_mass.min(b_mass);
+            if energy <= config.shatter_energy {
+                continue;
+            }
+
+            let (entity, body, (velocity, spin), received) = if a_radius <= b_radius {
+                (a_entity, &*a_body, before[0], impulse)
+            } else {
+                (b_entity, &*b_body, before[1], -impulse)
+            };
+            // a chain reaction scores when a shot started it
+            let by_player = a_shattered.is_some_and(|s| s.by_player)
+                || b_shattered.is_some_and(|s| s.by_player);
+
+            broken.push(entity);
+            commands.entity(entity).try_insert(DespawnAnimation {
+                timer: Timer::from_seconds(ANIMATION_DURATION, TimerMode::Once),
+            });
+            destroyed_writer.write(AsteroidDestroyed {
+                asteroid: entity,
+                position: contact,
+                velocity,
+                impulse: received,
+                size: body.radius,
+                size_type: size_type(body.radius),
+                by_player,
+                mined: false,
+            });
+
+            let fragments = fragment(
+                body.radius,
+                velocity,
+                spin,
+                contact - body.center,
+                received,
+                &gameconfig.asteroids.fragmentation,
+                &mut rng,
+            );
+            spawn_fragments(
+                &mut commands,
+                &assets,
+                &gameconfig,
+                body.tr,
+                &fragments,
+                by_player,
+                &mut rng,
+            );
         }
     }
 }
 
+/// Single rock with the volume, momentum and angular momentum of both, and
+/// its center of mass
+fn merge(a_body: &CollisionBody, b_body: &CollisionBody) -> (Vec3, Fragment) {
+    let mass = a_body.mass + b_body.mass;
+    let size = mass.cbrt();
+    let center = (a_body.tr * a_body.mass + b_body.tr * b_body.mass) / mass;
+    let velocity = (a_body.vel * a_body.mass + b_body.vel * b_body.mass) / mass;
+
+    let angular_momentum: Vec3 = [a_body, b_body]
+        .iter()
+        .map(|body| {
+            body.spin * body.inertia + (body.tr - center).cross((body.vel - velocity) * body.mass)
+        })
+        .sum();
+
+    (
+        center,
+        Fragment {
+            size,
+            offset: Vec3::ZERO,
+            velocity,
+            rotation_velocity: angular_momentum / sphere_inertia(mass, size),
+        },
+    )
+}
+
 pub fn asteroid_player_collision(
-    mut next_state: ResMut<NextState<GameOverState>>,
+    gameconfig: Res<GameConfig>,
+    mut impacts: MessageWriter<ShipImpact>,
     player_hitboxes: Query<(&Transform, &PlayerHitBox), Without<Asteroid>>,
-    mut player: Single<(&Transform, &mut Velocity), (With<Player>, Without<Asteroid>)>,
-    mut asteroids_query: Query<(&mut Transform, &Asteroid, &mut Velocity)>,
+    mut player: Single<
+        (
+            Entity,
+            &Transform,
+            &mut Velocity,
+            &mut RotationVelocity,
+            Option<&PhysicsMaterial>,
+        ),
+        (With<Player>, Without<Asteroid>),
+    >,
+    mut asteroids_query: Query<(
+        Entity,
+        &mut Transform,
+        &Asteroid,
+        &mut Velocity,
+        &mut RotationVelocity,
+        Option<&PhysicsMaterial>,
+    )>,
 ) {
+    let default_material = PhysicsMaterial::from(&gameconfig.physics.material);
+    let ship_material = player.4.copied().unwrap_or(default_material);
     for (hb_transform, player_hitbox) in &player_hitboxes {
-        let world_pos = player.0.translation + hb_transform.translation;
-        for (mut asteroid_transform, asteroid, mut asteroid_velocity) in &mut asteroids_query {
+        for (
+            asteroid_entity,
+            mut asteroid_transform,
+            asteroid,
+            mut asteroid_velocity,
+            mut asteroid_rotation,
+            asteroid_material,
+        ) in &mut asteroids_query
+        {
+            let world_pos =
+                player_hitbox.closest_point(player.1, hb_transform, asteroid_transform.translation);
             let dist = world_pos.distance(asteroid_transform.translation);
 
             if dist > player_hitbox.radius + asteroid.size {
                 continue;
             }
 
+            let asteroid_mass = asteroid.size.powi(3);
             let a_body = &mut CollisionBody {
                 tr: world_pos,
-                vel: **player.1,
+                center: player.1.translation,
+                vel: **player.2,
+                spin: **player.3,
                 radius: player_hitbox.radius,
                 mass: PLAYER_MASS,
+                inertia: sphere_inertia(PLAYER_MASS, PLAYER_RADIUS),
             };
             let b_body = &mut CollisionBody {
                 tr: asteroid_transform.translation,
+                center: asteroid_transform.translation,
                 vel: asteroid_velocity.0,
+                spin: asteroid_rotation.0,
                 radius: asteroid.size,
-                mass: asteroid.size.powi(3),
+                mass: asteroid_mass,
+                inertia: sphere_inertia(asteroid_mass, asteroid.size),
             };
 
-            mass_collision(a_body, b_body, dist);
+            let material = ship_material.combine(asteroid_material.unwrap_or(&default_material));
+            let impulse = mass_collision(a_body, b_body, dist, material);
+            if impulse == Vec3::ZERO {
+                continue; // already bouncing away
+            }
 
             asteroid_transform.translation = b_body.tr;
 
             asteroid_velocity.0 = b_body.vel;
-            **player.1 = a_body.vel;
-
-            next_state.set(GameOverState::Drift);
+            asteroid_rotation.0 = b_body.spin;
+            **player.2 = a_body.vel;
+            **player.3 = a_body.spin;
+
+            impacts.write(ShipImpact {
+                ship: player.0,
+                asteroid: asteroid_entity,
+                position: a_body.tr + (b_body.tr - a_body.tr).normalize_or_zero() * a_body.radius,
+                impulse,
+                size_type: size_type(asteroid.size),
+            });
             return;
         }
     }
 }
 
-pub fn get_score(size_type: &str) -> u32 {
-    return match size_type {
-        "XS" => 50,
-        "S" => 30,
-        "M" => 10,
-        "L" => 5,
-        "XL" => 1,
-        "XXL" => 1,
-        _ => 0,
-    };
-}
-
 pub fn asteroid_ammo_collision(
     mut commands: Commands,
-    mut score: ResMut<Score>,
+    gameconfig: Res<GameConfig>,
     assets: Res<AsteroidAssets>,
-    audio: Res<BoomSounds>,
-    master_volume: Res<MusicVolume>,
-    asteroids_query: Query<
-        (Entity, &Asteroid, &Transform),
+    mut destroyed_writer: MessageWriter<AsteroidDestroyed>,
+    mut asteroids_query: Query<
+        (
+            Entity,
+            &Asteroid,
+            &Transform,
+            &mut Velocity,
+            &mut RotationVelocity,
+            &PhysicsState,
+            &mut AsteroidDamage,
+        ),
         (Without<SpawnAnimation>, Without<DespawnAnimation>),
     >,
-    ammos_query: Query<(Entity, &Transform), With<Ammo>>,
+    mut ammos_query: Query<
+        (Entity, &Transform, &Velocity, &PhysicsState, &mut Ammo),
+        Without<Asteroid>,
+    >,
 ) {
     let mut rng = rand::rng();
+    let config = &gameconfig.asteroids.fragmentation;
 
-    for (ammo_entity, ammo_transform) in &ammos_query {
-        for (asteroid_entity, asteroid, asteroid_transform) in &asteroids_query {
-            let dist = ammo_transform
-                .translation
-                .distance(asteroid_transform.translation);
-
-            let ammo_radius = 1.0;
-            if dist > ammo_radius + asteroid.size {
-                continue;
-            }
-
-            let size_type = ASTEROID_SIZE_TYPES
-                [(asteroid.size / (ASTEROID_SIZE_TYPES_LEN as f32)).round() as usize];
-
-            score.value += get_score(size_type);
-
-            commands.entity(asteroid_entity).insert(DespawnAnimation {
-                timer: Timer::from_seconds(ANIMATION_DURATION, TimerMode::Once),
-            });
-            commands.entity(ammo_entity).despawn();
-            if asteroid.size < 2.0 {
-                return;
-            }
-
-            let new_size = asteroid.size / 2.0;
-
-            let new_size_type =
-                ASTEROID_SIZE_TYPES[(new_size / (ASTEROID_SIZE_TYPES_LEN as f32)).round() as usize];
-
-            let new_size_rounded = new_size.round();
-
-            let fw = ammo_transform.forward().normalize();
-            let helper = if fw.abs().z < 0.9 { Vec3::Z } else { Vec3::Y };
-
-            let u = fw.cross(helper).normalize();
-            let v = fw.cross(u).normalize();
-
-            let angle = rand::random::<f32>() * core::f32::consts::TAU;
-            let new_dir = (u * angle.cos() + v * angle.sin()).normalize();
-            let new_velocity = (new_dir * 0.3).normalize() * f(new_size);
-            let new_velocity_neg = (-new_dir * 0.3).normalize() * f(new_size);
-
-            let new_rotation_velocity = Vec3::new(
-                rng.random_range(-1.0..1.0),
-                rng.random_range(-1.0..1.0),
-                rng.random_range(-1.0..1.0),
+    // earliest contact of each projectile during the tick, swept relative to the asteroids
+    let mut hits: Vec<(f32, Entity, Entity, Vec3, Vec3)> = Vec::new();
+    for (ammo_entity, ammo_transform, _, ammo_state, ammo) in &ammos_query {
+        if !ammo.in_flight {
+            continue;
+        }
+        let ammo_start = ammo_state.previous_translation();
+        let hit = asteroids_query
+            .iter()
+            .filter_map(
+                |(asteroid_entity, asteroid, asteroid_transform, _, _, state, _)| {
+                    let start = ammo_start - state.previous_translation();
+                    let end = ammo_transform.translation - asteroid_transform.translation;
+                    let t = sweep_sphere(start, end, ammo.radius + asteroid.size)?;
+                    let contact = ammo_start.lerp(ammo_transform.translation, t);
+                    let normal = start.lerp(end, t).normalize_or_zero();
+                    Some((t, ammo_entity, asteroid_entity, contact, normal))
+                },
             )
-            .normalize()
-                * f(new_size)
-                * 0.3;
-
-            commands.spawn_batch([
-                (
-                    Mesh3d(
-                        assets.meshes.get(new_size_type).unwrap()[rng.random_range(0..4)].clone(),
-                    ),
-                    MeshMaterial3d(assets.materials.get(new_size_type).unwrap().clone()),
-                    Asteroid {
-                        size: new_size_rounded,
-                    },
-                    Transform {
-                        translation: asteroid_transform.translation + new_velocity * 0.1,
-                        scale: Vec3::new(new_size_rounded, new_size_rounded, new_size_rounded),
-                        rotation: Quat::from_rng(&mut rng),
-                        ..Default::default()
-                    },
-                    Velocity(new_velocity),
-                    RotationVelocity(new_rotation_velocity),
-                ),
-                (
-                    Mesh3d(
-                        assets.meshes.get(new_size_type).unwrap()[rng.random_range(0..4)].clone(),
-                    ),
-                    MeshMaterial3d(assets.materials.get(new_size_type).unwrap().clone()),
-                    Asteroid {
-                        size: new_size_rounded,
-                    },
-                    Transform {
-                        translation: asteroid_transform.translation + new_velocity_neg * 0.1,
-                        scale: Vec3::new(new_size_rounded, new_size_rounded, new_size_rounded),
-                        rotation: Quat::from_rng(&mut rng),
-
-                        ..Default::default()
-                    },
-                    Velocity(new_velocity_neg),
-                    RotationVelocity(-new_rotation_velocity),
-                ),
-            ]);
-
-            let texture_atlas = TextureAtlas {
-                layout: assets.explosion_layout.clone(),
-                index: 0,
-            };
+            .min_by(|a, b| a.0.total_cmp(&b.0));
+        hits.extend(hit);
+    }
+    hits.sort_by(|a, b| a.0.total_cmp(&b.0));
 
-            let mut rng = rand::rng();
-
-            if let Some(handle) = audio.booms.choose(&mut rng) {
-                commands.spawn((
-                    Sprite {
-                        image: assets.explosion_sheet.clone(),
-                        texture_atlas: Some(texture_atlas),
-                        ..default()
-                    },
-                    Sprite3d {
-                        pixels_per_metre: 360.,
-                        alpha_mode: AlphaMode::Blend,
-                        unlit: true,
-                        ..default()
-                    },
-                    AnimationTimer(Timer::from_seconds(0.08, TimerMode::Repeating)),
-                    AnimationDuration { frame_left: 7 },
-                    Transform {
-                        translation: ammo_transform.translation,
-                        rotation: ammo_transform.rotation,
-                        scale: asteroid_transform.scale * 5.0,
-                        ..Default::default()
-                    },
-                    children![(
-                        AudioPlayer::new(handle.clone()),
-                        PlaybackSettings {
-                            mode: bevy::audio::PlaybackMode::Despawn,
-                            volume: Volume::Linear(master_volume.volume),
-                            spatial: true,
-                            ..Default::default()
-                        },
-                        Transform::default(),
-                    )],
-                ));
-            }
-            return;
+    let mut destroyed: Vec<Entity> = Vec::new();
+    for (_, ammo_entity, asteroid_entity, contact, normal) in hits {
+        if destroyed.contains(&asteroid_entity) {
+            continue; // the projectile goes on, it may hit a fragment next tick
         }
+
+        let Ok((_, _, ammo_velocity, _, mut ammo)) = ammos_query.get_mut(ammo_entity) else {
+            continue;
+        };
+        let Ok((
+            asteroid_entity,
+            asteroid,
+            asteroid_transform,
+            mut asteroid_velocity,
+            mut asteroid_rotation_velocity,
+            _,
+            mut damage,
+        )) = asteroids_query.get_mut(asteroid_entity)
+        else {
+            continue;
+        };
+
+        ammo.in_flight = false;
+        let impact = normal * asteroid.size;
+        let impulse = (**ammo_velocity - **asteroid_velocity) * config.ammo_mass;
+
+        damage.0 += ammo.damage;
+        if damage.0 < asteroid.size * gameconfig.asteroids.hit_points {
+            // holds together, the hit only pushes it
+            let mass = asteroid.size.powi(3);
+            **asteroid_velocity += impulse / mass;
+            **asteroid_rotation_velocity +=
+                impact.cross(impulse) / sphere_inertia(mass, asteroid.size);
+            continue;
+        }
+
+        destroyed.push(asteroid_entity);
+        commands.entity(asteroid_entity).try_insert(DespawnAnimation {
+            timer: Timer::from_seconds(ANIMATION_DURATION, TimerMode::Once),
+        });
+        destroyed_writer.write(AsteroidDestroyed {
+            asteroid: asteroid_entity,
+            position: contact,
+            velocity: **asteroid_velocity,
+            impulse,
+            size: asteroid.size,
+            size_type: size_type(asteroid.size),
+            by_player: true,
+            mined: false,
+        });
+
+        let fragments = fragment(
+            asteroid.size,
+            **asteroid_velocity,
+            **asteroid_rotation_velocity,
+            impact,
+            impulse,
+            config,
+            &mut rng,
+        );
+        spawn_fragments(
+            &mut commands,
+            &assets,
+            &gameconfig,
+            asteroid_transform.translation,
+            &fragments,
+            true,
+            &mut rng,
+        );
     }
 }
 
 pub fn sun_player_collision(
     mut commands: Commands,
-    mut next_state: ResMut<NextState<GameOverState>>,
+    gameconfig: Res<GameConfig>,
+    mut contacts: MessageWriter<SunContact>,
     assets: Res<AsteroidAssets>,
     player_hitboxes: Query<(&Transform, &PlayerHitBox), Without<Sun>>,
-    mut player: Single<(&Transform, &mut Velocity), (With<Player>, Without<Sun>)>,
-    mut suns_query: Query<(Entity, &Transform, &Sun, &Velocity)>,
+    mut player: Single<
+        (
+            Entity,
+            &Transform,
+            &mut Velocity,
+            &mut RotationVelocity,
+            Option<&PhysicsMaterial>,
+        ),
+        (With<Player>, Without<Sun>),
+    >,
+    mut suns_query: Query<
+        (
+            Entity,
+            &Transform,
+            &Sun,
+            &Velocity,
+            Option<&PhysicsMaterial>,
+        ),
+        Without<DespawnAnimation>,
+    >,
 ) {
+    let default_material = PhysicsMaterial::from(&gameconfig.physics.material);
+    let ship_material = player.4.copied().unwrap_or(default_material);
     for (hb_transform, player_hitbox) in &player_hitboxes {
-        let world_pos = player.0.translation + hb_transform.translation;
-        for (sun_entity, sun_transform, sun, sun_velocity) in &mut suns_query {
+        for (sun_entity, sun_transform, sun, sun_velocity, sun_material) in &mut suns_query {
+            let world_pos =
+                player_hitbox.closest_point(player.1, hb_transform, sun_transform.translation);
             let dist = world_pos.distance(sun_transform.translation);
 
             if dist > player_hitbox.radius + sun.size {
                 continue;
             }
 
+            let sun_mass = sun.size.powi(3);
             let a_body = &mut CollisionBody {
                 tr: world_pos,
-                vel: **player.1,
+                center: player.1.translation,
+                vel: **player.2,
+                spin: **player.3,
                 radius: player_hitbox.radius,
                 mass: PLAYER_MASS,
+                inertia: sphere_inertia(PLAYER_MASS, PLAYER_RADIUS),
             };
             let b_body = &mut CollisionBody {
                 tr: sun_transform.translation,
+                center: sun_transform.translation,
                 vel: sun_velocity.0,
+                spin: Vec3::ZERO,
                 radius: sun.size,
-                mass: sun.size.powi(3),
+                mass: sun_mass,
+                inertia: sphere_inertia(sun_mass, sun.size),
             };
 
-            mass_collision(a_body, b_body, dist);
+            let material = ship_material.combine(sun_material.unwrap_or(&default_material));
+            let impulse = mass_collision(a_body, b_body, dist, material);
+            if impulse == Vec3::ZERO {
+                continue; // already bouncing away
+            }
 
-            **player.1 = a_body.vel;
+            **player.2 = a_body.vel;
+            **player.3 = a_body.spin;
 
-            next_state.set(GameOverState::Drift);
+            contacts.write(SunContact {
+                ship: player.0,
+                sun: sun_entity,
+                position: world_pos,
+                impulse,
+            });
             if sun.size <= SUN_SIZE / 8.0 + 0.1 {
                 // max 3 splits -> 2³ = 8 sun max
                 return;
@@ -330,7 +565,7 @@ pub fn sun_player_collision(
             });
             let new_sun_size = sun.size / 2.0;
 
-            let fw = (sun_transform.translation - player.0.translation).normalize();
+            let fw = (sun_transform.translation - player.1.translation).normalize();
             let helper = if fw.abs().z < 0.9 { Vec3::Z } else { Vec3::Y };
 
             let u = fw.cross(helper).normalize();
@@ -341,85 +576,130 @@ pub fn sun_player_collision(
             let new_velocity = new_dir * f(new_sun_size);
             let new_velocity_neg = -new_dir * f(new_sun_size);
 
-            let [sun_mesh, wireframe_mesh] = assets.sun_meshes.clone();
-            let [sun_material, sun_aura_material, wireframe_material] =
-                assets.sun_materials.clone();
-            let new_sun_scale = Vec3::new(new_sun_size, new_sun_size, new_sun_size);
-            commands.spawn((
-                Sun { size: new_sun_size },
-                Mesh3d(sun_mesh.clone()),
-                Transform {
-                    translation: sun_transform.translation + new_dir * new_sun_size,
-                    scale: new_sun_scale,
-                    ..default()
-                },
-                Velocity(new_velocity),
-                MeshMaterial3d(sun_material.clone()),
-                children![
-                    (PointLight {
-                        intensity: 2. * new_sun_size * 1_000_000_000.0,
-                        range: new_sun_size * 100.0,
-                        radius: new_sun_size,
-                        color: Color::WHITE,
-                        shadows_enabled: true,
-                        ..default()
-                    },),
-                    (
-                        Mesh3d(wireframe_mesh.clone()), // wireframe
-                        Transform {
-                            scale: Vec3::new(1.001, 1.001, 1.001),
-                            ..default()
-                        },
-                        MeshMaterial3d(wireframe_material.clone()),
-                    ),
-                    (
-                        Mesh3d(sun_mesh.clone()), // slightly larger
-                        Transform {
-                            scale: Vec3::new(1.1, 1.1, 1.1),
-                            ..default()
-                        },
-                        MeshMaterial3d(sun_aura_material.clone()),
-                    )
-                ],
+            commands.spawn(sun_bundle(
+                &assets,
+                new_sun_size,
+                sun_transform.translation + new_dir * new_sun_size,
+                new_velocity,
             ));
-            commands.spawn((
-                Sun { size: new_sun_size },
-                Mesh3d(sun_mesh.clone()),
-                Transform {
-                    translation: sun_transform.translation - new_dir * new_sun_size,
-                    scale: new_sun_scale,
-                    ..default()
-                },
-                Velocity(new_velocity_neg),
-                MeshMaterial3d(sun_material.clone()),
-                children![
-                    (PointLight {
-                        intensity: 2. * new_sun_size * 1_000_000_000.0,
-                        range: new_sun_size * 100.0,
-                        radius: new_sun_size,
-                        color: Color::WHITE,
-                        shadows_enabled: true,
-                        ..default()
-                    },),
-                    (
-                        Mesh3d(wireframe_mesh.clone()), // wireframe
-                        Transform {
-                            scale: Vec3::new(1.001, 1.001, 1.001),
-                            ..default()
-                        },
-                        MeshMaterial3d(wireframe_material.clone()),
-                    ),
-                    (
-                        Mesh3d(sun_mesh.clone()), // slightly larger
-                        Transform {
-                            scale: Vec3::new(1.1, 1.1, 1.1),
-                            ..default()
-                        },
-                        MeshMaterial3d(sun_aura_material.clone()),
-                    )
-                ],
+            commands.spawn(sun_bundle(
+                &assets,
+                new_sun_size,
+                sun_transform.translation - new_dir * new_sun_size,
+                new_velocity_neg,
             ));
             return;
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn body(tr: Vec3, vel: Vec3, spin: Vec3, radius: f32, mass: f32) -> CollisionBody {
+        CollisionBody {
+            tr,
+            center: tr,
+            vel,
+            spin,
+            radius,
+            mass,
+            inertia: sphere_inertia(mass, radius),
+        }
+    }
+
+    fn momentum(bodies: [&CollisionBody; 2]) -> (Vec3, Vec3) {
+        bodies.iter().fold((Vec3::ZERO, Vec3::ZERO), |(p, l), b| {
+            (
+                p + b.vel * b.mass,
+                l + b.spin * b.inertia + b.center.cross(b.vel * b.mass),
+            )
+        })
+    }
+
+    fn energy(bodies: [&CollisionBody; 2]) -> f32 {
+        bodies
+            .iter()
+            .map(|b| 0.5 * (b.mass * b.vel.length_squared() + b.inertia * b.spin.length_squared()))
+            .sum()
+    }
+
+    /// Bodies touching with an off-center approach, so both the normal and the friction parts act
+    fn pair() -> (CollisionBody, CollisionBody) {
+        (
+            body(Vec3::ZERO, Vec3::new(4.0, 1.0, 0.5), Vec3::Y, 2.0, 8.0),
+            body(
+                Vec3::new(3.0, 0.5, 0.0).normalize() * 5.0,
+                Vec3::new(-1.0, 0.0, 2.0),
+                Vec3::new(0.0, 0.0, -3.0),
+                3.0,
+                27.0,
+            ),
+        )
+    }
+
+    fn material(restitution: f32, friction: f32) -> PhysicsMaterial {
+        PhysicsMaterial {
+            restitution,
+            friction,
+        }
+    }
+
+    #[test]
+    fn mass_collision_conserves_momentum() {
+        for (restitution, friction) in [(1.0, 0.0), (1.0, 0.3), (0.5, 0.3), (0.0, 1.0)] {
+            let (mut a, mut b) = pair();
+            let (p0, l0) = momentum([&a, &b]);
+            let e0 = energy([&a, &b]);
+
+            let impulse = mass_collision(&mut a, &mut b, 5.0, material(restitution, friction));
+            assert_ne!(impulse, Vec3::ZERO);
+
+            let (p1, l1) = momentum([&a, &b]);
+            assert!(p0.distance(p1) < 1e-3, "linear {p0} -> {p1}");
+            assert!(l0.distance(l1) < 1e-2, "angular {l0} -> {l1}");
+
+            // elastic without friction, anything else can only take energy away
+            let e1 = energy([&a, &b]);
+            if restitution == 1.0 && friction == 0.0 {
+                assert!((e0 - e1).abs() < 1e-2, "energy {e0} -> {e1}");
+            } else {
+                assert!(e1 < e0, "energy {e0} -> {e1}");
+            }
+        }
+    }
+
+    #[test]
+    fn zero_restitution_stops_dead() {
+        let (mut a, mut b) = pair();
+        let n = (b.tr - a.tr).normalize();
+        a.spin = Vec3::ZERO;
+        b.spin = Vec3::ZERO;
+
+        mass_collision(&mut a, &mut b, 5.0, material(0.0, 0.0));
+        assert!((a.vel - b.vel).dot(n).abs() < 1e-4);
+    }
+
+    #[test]
+    fn combined_material() {
+        let combined = material(0.8, 0.25).combine(&material(0.2, 1.0));
+        assert_eq!(combined.restitution, 0.2);
+        assert!((combined.friction - 0.5).abs() < 1e-6);
+    }
+
+    #[test]
+    fn separating_bodies_do_not_collide() {
+        let (mut a, mut b) = pair();
+        a.vel = -a.vel;
+        b.vel = -b.vel;
+        a.spin = Vec3::ZERO;
+        b.spin = Vec3::ZERO;
+
+        assert_eq!(
+            mass_collision(&mut a, &mut b, 5.0, material(1.0, 0.3)),
+            Vec3::ZERO
+        );
+        assert_eq!(a.vel, -pair().0.vel);
+    }
+}
@@ -0,0 +1,220 @@
+use bevy::audio::Volume;
+use rand::seq::IndexedRandom;
+
+use crate::asteroids::messages::{AsteroidBounce, AsteroidDestroyed};
+use crate::asteroids::*;
+use crate::globals_structs::{MusicVolume, Score};
+use crate::powerups::PowerUps;
+use crate::spritesheet::{AnimationDuration, AnimationTimer};
+
+// change of speed under which a bounce stays silent
+const BOUNCE_THRESHOLD: f32 = 0.5;
+
+pub fn get_score(size_type: &str) -> u32 {
+    match size_type {
+        "XS" => 50,
+        "S" => 30,
+        "M" => 10,
+        "L" => 5,
+        "XL" => 1,
+        "XXL" => 1,
+        _ => 0,
+    }
+}
+
+pub fn score_destroyed(
+    mut destroyed: MessageReader<AsteroidDestroyed>,
+    mut score: ResMut<Score>,
+    powerups: Res<PowerUps>,
+) {
+    for message in destroyed.read().filter(|message| message.by_player) {
+        let points = get_score(message.size_type);
+        debug!("asteroid {} destroyed, +{points}", message.asteroid);
+        score.add(points, powerups.score_multiplier());
+    }
+}
+
+/// Thud when two rocks bounce hard enough, louder for the biggest of the pair
+pub fn bounce_sounds(
+    mut commands: Commands,
+    mut bounces: MessageReader<AsteroidBounce>,
+    audio: Res<BoomSounds>,
+    master_volume: Res<MusicVolume>,
+    asteroids: Query<&Asteroid>,
+) {
+    let mut rng = rand::rng();
+
+    for message in bounces.read() {
+        let size = asteroids
+            .iter_many([message.a, message.b])
+            .map(|asteroid| asteroid.size)
+            .fold(0.0, f32::max);
+        // impulse per unit of mass of the biggest rock, ie its change of speed
+        let strength = message.impulse.length() / size.powi(3).max(1.0);
+        if strength < BOUNCE_THRESHOLD {
+            continue;
+        }
+
+        if let Some(handle) = audio.booms.choose(&mut rng) {
+            commands.spawn((
+                AudioPlayer::new(handle.clone()),
+                PlaybackSettings {
+                    mode: bevy::audio::PlaybackMode::Despawn,
+                    volume: Volume::Linear(
+                        master_volume.volume / 100.0 * (size / 10.0).clamp(0.1, 1.0),
+                    ),
+                    spatial: true,
+                    ..Default::default()
+                },
+                Transform::from_translation(message.position),
+            ));
+        }
+    }
+}
+
+/// Explosion sprite and boom sound where the asteroid was hit
+pub fn explode_destroyed(
+    mut commands: Commands,
+    mut destroyed: MessageReader<AsteroidDestroyed>,
+    assets: Res<AsteroidAssets>,
+    audio: Res<BoomSounds>,
+    master_volume: Res<MusicVolume>,
+) {
+    let mut rng = rand::rng();
+
+    for message in destroyed.read() {
+        let texture_atlas = TextureAtlas {
+            layout: assets.explosion_layout.clone(),
+            index: 0,
+        };
+
+        // faces the shooter, like the projectile did
+        let rotation =
+            Quat::from_rotation_arc(Vec3::Z, message.impulse.try_normalize().unwrap_or(Vec3::Z));
+
+        if let Some(handle) = audio.booms.choose(&mut rng) {
+            commands.spawn((
+                Sprite {
+                    image: assets.explosion_sheet.clone(),
+                    texture_atlas: Some(texture_atlas),
+                    ..default()
+                },
+                Sprite3d {
+                    pixels_per_metre: 360.,
+                    alpha_mode: AlphaMode::Blend,
+                    unlit: true,
+                    ..default()
+                },
+                AnimationTimer(Timer::from_seconds(0.08, TimerMode::Repeating)),
+                AnimationDuration { frame_left: 7 },
+                Transform {
+                    translation: message.position,
+                    rotation,
+                    scale: Vec3::splat(message.size * 5.0),
+                },
+                children![(
+                    AudioPlayer::new(handle.clone()),
+                    PlaybackSettings {
+                        mode: bevy::audio::PlaybackMode::Despawn,
+                        volume: Volume::Linear(master_volume.volume),
+                        spatial: true,
+                        ..Default::default()
+                    },
+                    Transform::default(),
+                )],
+            ));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::destroyed;
+
+    fn sounds(app: &mut App) -> usize {
+        let mut query = app.world_mut().query::<&AudioPlayer>();
+        query.iter(app.world()).count()
+    }
+
+    #[test]
+    fn only_player_kills_score() {
+        let mut app = App::new();
+        app.add_message::<AsteroidDestroyed>()
+            .init_resource::<Score>()
+            .init_resource::<PowerUps>()
+            .add_systems(Update, score_destroyed);
+
+        app.world_mut().write_message(destroyed("XS"));
+        app.world_mut().write_message(AsteroidDestroyed {
+            by_player: false,
+            ..destroyed("M")
+        });
+        app.update();
+
+        assert_eq!(app.world().resource::<Score>().value, get_score("XS"));
+    }
+
+    #[test]
+    fn only_hard_bounces_thud() {
+        let mut app = App::new();
+        app.add_message::<AsteroidBounce>()
+            .insert_resource(BoomSounds {
+                booms: vec![Handle::default()],
+            })
+            .insert_resource(MusicVolume { volume: 50.0 })
+            .add_systems(Update, bounce_sounds);
+        let a = app.world_mut().spawn(Asteroid { size: 2.0 }).id();
+        let b = app.world_mut().spawn(Asteroid { size: 1.0 }).id();
+        let bounce = |impulse: Vec3| AsteroidBounce {
+            a,
+            b,
+            position: Vec3::ZERO,
+            impulse,
+        };
+
+        // change of speed of the biggest rock, 0.1 then 1.0
+        app.world_mut().write_message(bounce(Vec3::X * 0.8));
+        app.update();
+        assert_eq!(sounds(&mut app), 0);
+
+        app.world_mut().write_message(bounce(Vec3::X * 8.0));
+        app.update();
+        assert_eq!(sounds(&mut app), 1);
+    }
+
+    #[test]
+    fn every_destruction_explodes() {
+        let mut app = App::new();
+        app.add_message::<AsteroidDestroyed>()
+            .insert_resource(AsteroidAssets {
+                meshes: default(),
+                materials: default(),
+                explosion_sheet: default(),
+                explosion_layout: default(),
+                sun_meshes: default(),
+                sun_materials: default(),
+            })
+            .insert_resource(BoomSounds {
+                booms: vec![Handle::default()],
+            })
+            .insert_resource(MusicVolume { volume: 50.0 })
+            .add_systems(Update, explode_destroyed);
+
+        app.world_mut().write_message(AsteroidDestroyed {
+            position: Vec3::new(1.0, 2.0, 3.0),
+            size: 4.0,
+            ..destroyed("L")
+        });
+        app.update();
+
+        let mut explosions = app
+            .world_mut()
+            .query_filtered::<&Transform, With<AnimationTimer>>();
+        let transforms: Vec<&Transform> = explosions.iter(app.world()).collect();
+        assert_eq!(transforms.len(), 1);
+        assert_eq!(transforms[0].translation, Vec3::new(1.0, 2.0, 3.0));
+        assert_eq!(transforms[0].scale, Vec3::splat(20.0));
+        assert_eq!(sounds(&mut app), 1);
+    }
+}
@@ -0,0 +1,176 @@
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+
+use bevy::math::Vec3;
+
+use crate::asteroids::generator::{generate_lods, variant_seed, AsteroidMeshData};
+use crate::asteroids::ASTEROID_SIZE_TYPES;
+use crate::config::structs::GameConfig;
+
+const USAGE: &str = "usage: AST3ROID generate-asteroids [--out <dir>] [--format glb|obj] [--variants <n>] [--seed <n>]";
+
+/// `generate-asteroids` subcommand: writes every variant and LOD of every size class to disk
+pub fn run_cli(gameconfig: &GameConfig, args: &[String]) {
+    let mut config = gameconfig.asteroids.generator.clone();
+    let mut out = String::from("assets/asteroids");
+    let mut format = String::from("glb");
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let Some(value) = args.next() else {
+            eprintln!("{USAGE}");
+            return;
+        };
+        match arg.as_str() {
+            "--out" => out = value.clone(),
+            "--format" => format = value.clone(),
+            "--variants" => config.variants = value.parse().unwrap_or(config.variants),
+            "--seed" => config.seed = value.parse().unwrap_or(config.seed),
+            _ => {
+                eprintln!("{USAGE}");
+                return;
+            }
+        }
+    }
+
+    if format != "glb" && format != "obj" {
+        eprintln!("{USAGE}");
+        return;
+    }
+    if let Err(e) = fs::create_dir_all(&out) {
+        eprintln!("Cannot create {out}: {e}");
+        return;
+    }
+
+    for (class, asteroid_type) in ASTEROID_SIZE_TYPES.iter().enumerate() {
+        for variant in 0..config.variants {
+            let lods = generate_lods(variant_seed(config.seed, class, variant), &config);
+            for (level, data) in lods.iter().enumerate() {
+                let name = if level == 0 {
+                    format!("{asteroid_type}{variant}.{format}")
+                } else {
+                    format!("{asteroid_type}{variant}_lod{level}.{format}")
+                };
+                let path = Path::new(&out).join(name);
+                let result = match format.as_str() {
+                    "obj" => write_obj(&path, data),
+                    _ => write_glb(&path, data),
+                };
+                match result {
+                    Ok(()) => println!("wrote {}", path.display()),
+                    Err(e) => eprintln!("Cannot write {}: {e}", path.display()),
+                }
+            }
+        }
+    }
+}
+
+pub fn write_obj(path: &Path, data: &AsteroidMeshData) -> io::Result<()> {
+    let mut file = BufWriter::new(File::create(path)?);
+
+    for [x, y, z] in &data.positions {
+        writeln!(file, "v {x} {y} {z}")?;
+    }
+    for [u, v] in &data.uvs {
+        // obj puts the v origin at the bottom
+        writeln!(file, "vt {u} {}", 1.0 - v)?;
+    }
+    for [x, y, z] in &data.normals {
+        writeln!(file, "vn {x} {y} {z}")?;
+    }
+    for tri in data.indices.chunks_exact(3) {
+        let [a, b, c] = [tri[0] + 1, tri[1] + 1, tri[2] + 1];
+        writeln!(file, "f {a}/{a}/{a} {b}/{b}/{b} {c}/{c}/{c}")?;
+    }
+
+    file.flush()
+}
+
+/// Minimal glTF binary with a single mesh, loadable as `#Mesh0/Primitive0`
+pub fn write_glb(path: &Path, data: &AsteroidMeshData) -> io::Result<()> {
+    let mut bin: Vec<u8> = Vec::new();
+    let mut views: Vec<(usize, usize, u32)> = Vec::new();
+
+    for (bytes, target) in [
+        (bytemuck::cast_slice::<[f32; 3], u8>(&data.positions), 34962),
+        (bytemuck::cast_slice::<[f32; 3], u8>(&data.normals), 34962),
+        (bytemuck::cast_slice::<[f32; 2], u8>(&data.uvs), 34962),
+        (bytemuck::cast_slice::<u32, u8>(&data.indices), 34963),
+    ] {
+        views.push((bin.len(), bytes.len(), target));
+        bin.extend_from_slice(bytes);
+    }
+
+    let (min, max) = data
+        .positions
+        .iter()
+        .map(|p| Vec3::from_array(*p))
+        .fold((Vec3::MAX, Vec3::MIN), |(min, max), p| {
+            (min.min(p), max.max(p))
+        });
+
+    let buffer_views = views
+        .iter()
+        .map(|(offset, length, target)| {
+            format!(
+                r#"{{"buffer":0,"byteOffset":{offset},"byteLength":{length},"target":{target}}}"#
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(",");
+
+    let vertex_count = data.positions.len();
+    let index_count = data.indices.len();
+    let mut json = format!(
+        concat!(
+            r#"{{"asset":{{"version":"2.0","generator":"AST3ROID"}},"#,
+            r#""scene":0,"scenes":[{{"nodes":[0]}}],"nodes":[{{"mesh":0}}],"#,
+            r#""meshes":[{{"primitives":[{{"attributes":{{"POSITION":0,"NORMAL":1,"TEXCOORD_0":2}},"indices":3}}]}}],"#,
+            r#""buffers":[{{"byteLength":{}}}],"bufferViews":[{}],"#,
+            r#""accessors":["#,
+            r#"{{"bufferView":0,"componentType":5126,"count":{},"type":"VEC3","min":[{},{},{}],"max":[{},{},{}]}},"#,
+            r#"{{"bufferView":1,"componentType":5126,"count":{},"type":"VEC3"}},"#,
+            r#"{{"bufferView":2,"componentType":5126,"count":{},"type":"VEC2"}},"#,
+            r#"{{"bufferView":3,"componentType":5125,"count":{},"type":"SCALAR"}}]}}"#,
+        ),
+        bin.len(),
+        buffer_views,
+        vertex_count,
+        min.x,
+        min.y,
+        min.z,
+        max.x,
+        max.y,
+        max.z,
+        vertex_count,
+        vertex_count,
+        index_count,
+    )
+    .into_bytes();
+
+    // both chunks must be 4 bytes aligned
+    while !json.len().is_multiple_of(4) {
+        json.push(b' ');
+    }
+    while !bin.len().is_multiple_of(4) {
+        bin.push(0);
+    }
+
+    let total_length = 12 + 8 + json.len() + 8 + bin.len();
+
+    let mut file = BufWriter::new(File::create(path)?);
+    file.write_all(&0x4654_6C67_u32.to_le_bytes())?; // "glTF"
+    file.write_all(&2_u32.to_le_bytes())?;
+    file.write_all(&(total_length as u32).to_le_bytes())?;
+
+    file.write_all(&(json.len() as u32).to_le_bytes())?;
+    file.write_all(&0x4E4F_534A_u32.to_le_bytes())?; // "JSON"
+    file.write_all(&json)?;
+
+    file.write_all(&(bin.len() as u32).to_le_bytes())?;
+    file.write_all(&0x004E_4942_u32.to_le_bytes())?; // "BIN"
+    file.write_all(&bin)?;
+
+    file.flush()
+}
@@ -0,0 +1,210 @@
+use rand::Rng;
+
+use crate::asteroids::utils::f;
+use crate::asteroids::*;
+use crate::config::structs::{FragmentationConfig, GameConfig};
+use crate::physics::{sphere_inertia, RotationVelocity};
+
+/// Piece of a broken asteroid, relative to the parent center
+pub struct Fragment {
+    pub size: f32,
+    pub offset: Vec3,
+    pub velocity: Vec3,
+    pub rotation_velocity: Vec3,
+}
+
+/// Rock too small to be an asteroid, shrinks and disappears
+#[derive(Component)]
+pub struct Debris {
+    size: f32,
+    lifetime: Timer,
+}
+
+/// Fresh fragment. It cannot merge back yet, and its own breakups score
+/// while `by_player` tells the chain was started by a shot
+#[derive(Component)]
+pub struct Shattered {
+    pub by_player: bool,
+    timer: Timer,
+}
+
+/// Splits an asteroid hit at `impact` (relative to its center) by a projectile
+/// carrying `impulse`. The volume of the fragments adds up to the parent one and
+/// their total momentum is the parent momentum plus `impulse`
+pub fn fragment(
+    size: f32,
+    velocity: Vec3,
+    rotation_velocity: Vec3,
+    impact: Vec3,
+    impulse: Vec3,
+    config: &FragmentationConfig,
+    rng: &mut impl Rng,
+) -> Vec<Fragment> {
+    let mass = size.powi(3);
+    let count = rng.random_range(config.min_fragments..=config.max_fragments);
+
+    // u^distribution skews toward one big chunk and many small ones
+    let weights: Vec<f32> = (0..count)
+        .map(|_| rng.random_range(0.05_f32..1.0).powf(config.distribution))
+        .collect();
+    let total_weight: f32 = weights.iter().sum();
+
+    // solid sphere inertia, the off-center hit makes the whole rock spin
+    let inertia = sphere_inertia(mass, size);
+    let spin = rotation_velocity + impact.cross(impulse) / inertia;
+    let center_velocity = velocity + impulse / mass;
+
+    let mut fragments: Vec<Fragment> = weights
+        .iter()
+        .map(|weight| {
+            let fragment_size = size * (weight / total_weight).cbrt();
+            let direction = Vec3::new(
+                rng.random_range(-1.0..1.0),
+                rng.random_range(-1.0..1.0),
+                rng.random_range(-1.0..1.0),
+            )
+            .normalize_or(Vec3::Y);
+            let offset = direction * (size - fragment_size).max(fragment_size * 0.5);
+
+            Fragment {
+                size: fragment_size,
+                offset,
+                // ejection away from the center, faster for small pieces
+                velocity: direction * config.ejection_speed * f(fragment_size),
+                rotation_velocity: Vec3::ZERO,
+            }
+        })
+        .collect();
+
+    // remove the drift of the center of mass so the pieces only add up to the impact
+    let (mean_offset, mean_kick) =
+        fragments
+            .iter()
+            .fold((Vec3::ZERO, Vec3::ZERO), |(o, k), frag| {
+                let m = frag.size.powi(3) / mass;
+                (o + frag.offset * m, k + frag.velocity * m)
+            });
+
+    for frag in &mut fragments {
+        frag.offset -= mean_offset;
+        frag.velocity += center_velocity - mean_kick + spin.cross(frag.offset);
+        frag.rotation_velocity = spin
+            + Vec3::new(
+                rng.random_range(-1.0..1.0),
+                rng.random_range(-1.0..1.0),
+                rng.random_range(-1.0..1.0),
+            ) * 0.1;
+    }
+
+    fragments
+}
+
+/// Spawns the pieces of a broken asteroid centered on `position`, the ones under
+/// `min_size` as debris. Returns the asteroids spawned
+pub fn spawn_fragments(
+    commands: &mut Commands,
+    assets: &AsteroidAssets,
+    gameconfig: &GameConfig,
+    position: Vec3,
+    fragments: &[Fragment],
+    by_player: bool,
+    rng: &mut impl Rng,
+) -> Vec<Entity> {
+    let config = &gameconfig.asteroids.fragmentation;
+    let mut spawned = Vec::new();
+
+    for frag in fragments {
+        if frag.size < config.min_size {
+            spawn_debris(commands, assets, config, position, frag, rng);
+            continue;
+        }
+
+        let size_type = size_type(frag.size);
+        let (mesh, lod) = assets.random_mesh(size_type, rng);
+        let entity = commands
+            .spawn((
+                mesh,
+                lod,
+                MeshMaterial3d(assets.materials.get(size_type).unwrap().clone()),
+                // not rounded, the fragments keep the exact volume of the parent
+                Asteroid { size: frag.size },
+                Transform {
+                    translation: position + frag.offset,
+                    scale: Vec3::splat(frag.size),
+                    rotation: Quat::from_rng(rng),
+                },
+                Velocity(frag.velocity),
+                RotationVelocity(frag.rotation_velocity),
+                material(gameconfig, size_type),
+                Shattered {
+                    by_player,
+                    timer: Timer::from_seconds(
+                        gameconfig.asteroids.impacts.chain_duration,
+                        TimerMode::Once,
+                    ),
+                },
+            ))
+            .id();
+        spawned.push(entity);
+    }
+
+    spawned
+}
+
+pub fn expire_shattered(
+    time: Res<Time>,
+    mut commands: Commands,
+    mut query: Query<(Entity, &mut Shattered)>,
+) {
+    for (entity, mut shattered) in &mut query {
+        if shattered.timer.tick(time.delta()).just_finished() {
+            commands.entity(entity).remove::<Shattered>();
+        }
+    }
+}
+
+pub fn spawn_debris(
+    commands: &mut Commands,
+    assets: &AsteroidAssets,
+    config: &FragmentationConfig,
+    position: Vec3,
+    frag: &Fragment,
+    rng: &mut impl Rng,
+) {
+    let (mesh, _) = assets.random_mesh(ASTEROID_SIZE_TYPES[0], rng);
+    commands.spawn((
+        mesh,
+        MeshMaterial3d(
+            assets
+                .materials
+                .get(ASTEROID_SIZE_TYPES[0])
+                .unwrap()
+                .clone(),
+        ),
+        Debris {
+            size: frag.size,
+            lifetime: Timer::from_seconds(config.debris_lifetime, TimerMode::Once),
+        },
+        Transform {
+            translation: position + frag.offset,
+            scale: Vec3::splat(frag.size),
+            rotation: Quat::from_rng(rng),
+        },
+        Velocity(frag.velocity),
+        RotationVelocity(frag.rotation_velocity),
+    ));
+}
+
+pub fn animate_debris(
+    time: Res<Time>,
+    mut commands: Commands,
+    mut query: Query<(Entity, &mut Transform, &mut Debris)>,
+) {
+    for (entity, mut transform, mut debris) in &mut query {
+        debris.lifetime.tick(time.delta());
+        transform.scale = Vec3::splat((1.0 - debris.lifetime.fraction()) * debris.size);
+        if debris.lifetime.is_finished() {
+            commands.entity(entity).despawn();
+        }
+    }
+}
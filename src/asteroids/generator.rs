@@ -0,0 +1,271 @@
+use bevy::asset::RenderAssetUsages;
+use bevy::math::Vec3;
+use bevy::mesh::{Indices, Mesh};
+use bevy::platform::collections::HashMap;
+use bevy::render::render_resource::PrimitiveTopology;
+
+use crate::config::structs::AsteroidGeneratorConfig;
+
+pub struct AsteroidMeshData {
+    pub positions: Vec<[f32; 3]>,
+    pub normals: Vec<[f32; 3]>,
+    pub uvs: Vec<[f32; 2]>,
+    pub indices: Vec<u32>,
+}
+
+impl AsteroidMeshData {
+    pub fn to_mesh(&self) -> Mesh {
+        let mut mesh = Mesh::new(PrimitiveTopology::TriangleList, RenderAssetUsages::all());
+
+        mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, self.positions.clone());
+        mesh.insert_attribute(Mesh::ATTRIBUTE_NORMAL, self.normals.clone());
+        mesh.insert_attribute(Mesh::ATTRIBUTE_UV_0, self.uvs.clone());
+        mesh.insert_indices(Indices::U32(self.indices.clone()));
+
+        mesh
+    }
+}
+
+/// Seed of one variant of one size class, so every class gets its own set of rocks
+pub fn variant_seed(base: u64, class: usize, variant: usize) -> u32 {
+    let mut h = base
+        ^ (class as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15)
+        ^ (variant as u64).wrapping_mul(0xC2B2_AE3D_27D4_EB4F);
+    h ^= h >> 33;
+    h = h.wrapping_mul(0xFF51_AFD7_ED55_8CCD);
+    h ^= h >> 33;
+    h as u32
+}
+
+/// Builds every LOD of one asteroid, from the finest (index 0) to the coarsest.
+/// All levels sample the same noise so they share the same silhouette.
+pub fn generate_lods(seed: u32, config: &AsteroidGeneratorConfig) -> Vec<AsteroidMeshData> {
+    (0..=config.lod_levels)
+        .map(|level| config.subdivisions.saturating_sub(level))
+        .map(|subdivisions| generate_asteroid(seed, subdivisions, config))
+        .collect()
+}
+
+pub fn generate_asteroid(
+    seed: u32,
+    subdivisions: u32,
+    config: &AsteroidGeneratorConfig,
+) -> AsteroidMeshData {
+    let (mut directions, mut indices) = icosphere(subdivisions);
+
+    let mut positions: Vec<Vec3> = directions
+        .iter()
+        .map(|dir| *dir * (1.0 + config.strength * fbm(seed, *dir * config.frequency, 4)))
+        .collect();
+
+    let mut normals = vec![Vec3::ZERO; positions.len()];
+    for tri in indices.chunks_exact(3) {
+        let [a, b, c] = [tri[0] as usize, tri[1] as usize, tri[2] as usize];
+        // not normalized: bigger faces weigh more in the smoothed normal
+        let face_normal = (positions[b] - positions[a]).cross(positions[c] - positions[a]);
+        normals[a] += face_normal;
+        normals[b] += face_normal;
+        normals[c] += face_normal;
+    }
+
+    let mut uvs: Vec<[f32; 2]> = directions
+        .iter()
+        .map(|dir| {
+            [
+                dir.z.atan2(dir.x) / core::f32::consts::TAU + 0.5,
+                dir.y.clamp(-1.0, 1.0).acos() / core::f32::consts::PI,
+            ]
+        })
+        .collect();
+
+    // a triangle across the u = 0/1 seam would stretch over the whole texture,
+    // its vertices on the low side use a copy wrapped past u = 1 instead
+    let mut wrapped: HashMap<u32, u32> = HashMap::default();
+    for tri in indices.chunks_exact_mut(3) {
+        let (low, high) = tri.iter().fold((f32::MAX, f32::MIN), |(low, high), index| {
+            let u = uvs[*index as usize][0];
+            (low.min(u), high.max(u))
+        });
+        if high - low <= 0.5 {
+            continue;
+        }
+
+        for index in tri.iter_mut() {
+            let i = *index as usize;
+            if uvs[i][0] >= 0.5 {
+                continue;
+            }
+            *index = *wrapped.entry(*index).or_insert_with(|| {
+                positions.push(positions[i]);
+                normals.push(normals[i]);
+                directions.push(directions[i]);
+                uvs.push([uvs[i][0] + 1.0, uvs[i][1]]);
+                (positions.len() - 1) as u32
+            });
+        }
+    }
+
+    AsteroidMeshData {
+        positions: positions.iter().map(|p| p.to_array()).collect(),
+        normals: normals
+            .iter()
+            .zip(directions.iter())
+            .map(|(n, dir)| n.try_normalize().unwrap_or(*dir).to_array())
+            .collect(),
+        uvs,
+        indices,
+    }
+}
+
+fn icosphere(subdivisions: u32) -> (Vec<Vec3>, Vec<u32>) {
+    let t = (1.0 + 5.0_f32.sqrt()) / 2.0;
+
+    let mut vertices: Vec<Vec3> = [
+        (-1.0, t, 0.0),
+        (1.0, t, 0.0),
+        (-1.0, -t, 0.0),
+        (1.0, -t, 0.0),
+        (0.0, -1.0, t),
+        (0.0, 1.0, t),
+        (0.0, -1.0, -t),
+        (0.0, 1.0, -t),
+        (t, 0.0, -1.0),
+        (t, 0.0, 1.0),
+        (-t, 0.0, -1.0),
+        (-t, 0.0, 1.0),
+    ]
+    .iter()
+    .map(|(x, y, z)| Vec3::new(*x, *y, *z).normalize())
+    .collect();
+
+    let mut faces: Vec<[u32; 3]> = vec![
+        [0, 11, 5],
+        [0, 5, 1],
+        [0, 1, 7],
+        [0, 7, 10],
+        [0, 10, 11],
+        [1, 5, 9],
+        [5, 11, 4],
+        [11, 10, 2],
+        [10, 7, 6],
+        [7, 1, 8],
+        [3, 9, 4],
+        [3, 4, 2],
+        [3, 2, 6],
+        [3, 6, 8],
+        [3, 8, 9],
+        [4, 9, 5],
+        [2, 4, 11],
+        [6, 2, 10],
+        [8, 6, 7],
+        [9, 8, 1],
+    ];
+
+    for _ in 0..subdivisions {
+        let mut midpoints: HashMap<(u32, u32), u32> = HashMap::default();
+        let mut next = Vec::with_capacity(faces.len() * 4);
+
+        for [a, b, c] in faces {
+            let ab = midpoint(&mut vertices, &mut midpoints, a, b);
+            let bc = midpoint(&mut vertices, &mut midpoints, b, c);
+            let ca = midpoint(&mut vertices, &mut midpoints, c, a);
+
+            next.extend([[a, ab, ca], [b, bc, ab], [c, ca, bc], [ab, bc, ca]]);
+        }
+        faces = next;
+    }
+
+    // make sure every triangle is counter-clockwise seen from outside
+    let indices = faces
+        .into_iter()
+        .flat_map(|[a, b, c]| {
+            let (pa, pb, pc) = (
+                vertices[a as usize],
+                vertices[b as usize],
+                vertices[c as usize],
+            );
+            if (pb - pa).cross(pc - pa).dot(pa) < 0.0 {
+                [a, c, b]
+            } else {
+                [a, b, c]
+            }
+        })
+        .collect();
+
+    (vertices, indices)
+}
+
+fn midpoint(
+    vertices: &mut Vec<Vec3>,
+    midpoints: &mut HashMap<(u32, u32), u32>,
+    a: u32,
+    b: u32,
+) -> u32 {
+    let key = (a.min(b), a.max(b));
+    if let Some(index) = midpoints.get(&key) {
+        return *index;
+    }
+
+    let index = vertices.len() as u32;
+    vertices.push(((vertices[a as usize] + vertices[b as usize]) / 2.0).normalize());
+    midpoints.insert(key, index);
+    index
+}
+
+// Value noise in [-1, 1]
+
+fn lattice(seed: u32, x: i32, y: i32, z: i32) -> f32 {
+    let mut h = seed
+        ^ (x as u32).wrapping_mul(0x8DA6_B343)
+        ^ (y as u32).wrapping_mul(0xD816_3841)
+        ^ (z as u32).wrapping_mul(0xCB1A_B31F);
+    h ^= h >> 13;
+    h = h.wrapping_mul(0x5BD1_E995);
+    h ^= h >> 15;
+
+    (h as f32 / u32::MAX as f32) * 2.0 - 1.0
+}
+
+fn value_noise(seed: u32, p: Vec3) -> f32 {
+    let cell = p.floor();
+    let local = p - cell;
+    let (x, y, z) = (cell.x as i32, cell.y as i32, cell.z as i32);
+
+    let smooth = local * local * (Vec3::splat(3.0) - 2.0 * local);
+    let lerp = |a: f32, b: f32, t: f32| a + (b - a) * t;
+
+    let x00 = lerp(lattice(seed, x, y, z), lattice(seed, x + 1, y, z), smooth.x);
+    let x10 = lerp(
+        lattice(seed, x, y + 1, z),
+        lattice(seed, x + 1, y + 1, z),
+        smooth.x,
+    );
+    let x01 = lerp(
+        lattice(seed, x, y, z + 1),
+        lattice(seed, x + 1, y, z + 1),
+        smooth.x,
+    );
+    let x11 = lerp(
+        lattice(seed, x, y + 1, z + 1),
+        lattice(seed, x + 1, y + 1, z + 1),
+        smooth.x,
+    );
+
+    lerp(lerp(x00, x10, smooth.y), lerp(x01, x11, smooth.y), smooth.z)
+}
+
+fn fbm(seed: u32, p: Vec3, octaves: u32) -> f32 {
+    let mut sum = 0.0;
+    let mut amplitude = 1.0;
+    let mut total = 0.0;
+    let mut frequency = 1.0;
+
+    for octave in 0..octaves {
+        sum += value_noise(seed.wrapping_add(octave), p * frequency) * amplitude;
+        total += amplitude;
+        amplitude *= 0.5;
+        frequency *= 2.0;
+    }
+
+    sum / total
+}
@@ -0,0 +1,46 @@
+use bevy::prelude::*;
+
+/// An asteroid broke apart, its fragments are already spawned
+#[derive(Message, Debug, Clone)]
+pub struct AsteroidDestroyed {
+    pub asteroid: Entity,
+    pub position: Vec3,
+    pub velocity: Vec3,
+    pub impulse: Vec3,
+    pub size: f32,
+    pub size_type: &'static str,
+    // shot, or shattered by a chain reaction a shot started
+    pub by_player: bool,
+    // broken down by the mining beam, yields more resources
+    pub mined: bool,
+}
+
+/// An asteroid hit the ship
+#[derive(Message, Debug, Clone)]
+pub struct ShipImpact {
+    pub ship: Entity,
+    pub asteroid: Entity,
+    pub position: Vec3,
+    // received by the ship
+    pub impulse: Vec3,
+    pub size_type: &'static str,
+}
+
+/// The ship touched a sun
+#[derive(Message, Debug, Clone)]
+pub struct SunContact {
+    pub ship: Entity,
+    pub sun: Entity,
+    pub position: Vec3,
+    pub impulse: Vec3,
+}
+
+/// Two asteroids bounced off each other
+#[derive(Message, Debug, Clone)]
+pub struct AsteroidBounce {
+    pub a: Entity,
+    pub b: Entity,
+    pub position: Vec3,
+    // received by `a`, `b` got the opposite
+    pub impulse: Vec3,
+}
@@ -1,27 +1,50 @@
 use bevy::platform::collections::HashMap;
 use bevy::prelude::*;
 use bevy_sprite3d::Sprite3d;
+use rand::Rng;
 
+use crate::config::structs::GameConfig;
 use crate::game_over::GameOverState;
 use crate::game_states::GameState;
-use crate::physics::Velocity;
+use crate::menu::structs::MenuState;
+use crate::physics::gravity::GravitySource;
+use crate::physics::{PhysicsMaterial, PhysicsSystems, Velocity};
 
 pub mod collision;
+pub mod effects;
+pub mod export;
+pub mod fragmentation;
+pub mod generator;
+pub mod messages;
+pub mod patterns;
 pub mod spawn;
 pub mod utils;
 
 #[derive(Component)]
+#[require(AsteroidDamage)]
 pub struct Asteroid {
     pub size: f32, // 1.0 - 10.0
 }
+
+/// Damage taken from projectiles, breaks at `size * hit_points`
+#[derive(Component, Default)]
+pub struct AsteroidDamage(pub f32);
 #[derive(Component)]
 pub struct Sun {
     pub size: f32, // 1.0
 }
 
+/// Mesh variant of an asteroid and the LOD currently displayed
+#[derive(Component)]
+pub struct AsteroidLod {
+    pub variant: usize,
+    pub level: usize,
+}
+
 #[derive(Resource)]
 pub struct AsteroidAssets {
-    meshes: HashMap<String, Vec<Handle<Mesh>>>,
+    // size type -> variants -> LODs, finest first
+    meshes: HashMap<String, Vec<Vec<Handle<Mesh>>>>,
     materials: HashMap<String, Handle<StandardMaterial>>,
     explosion_sheet: Handle<Image>,
     explosion_layout: Handle<TextureAtlasLayout>,
@@ -29,6 +52,18 @@ pub struct AsteroidAssets {
     sun_materials: [Handle<StandardMaterial>; 3],
 }
 
+impl AsteroidAssets {
+    pub fn random_mesh(&self, size_type: &str, rng: &mut impl Rng) -> (Mesh3d, AsteroidLod) {
+        let variants = self.meshes.get(size_type).unwrap();
+        let variant = rng.random_range(0..variants.len());
+
+        (
+            Mesh3d(variants[variant][0].clone()),
+            AsteroidLod { variant, level: 0 },
+        )
+    }
+}
+
 #[derive(Resource)]
 pub struct BoomSounds {
     pub booms: Vec<Handle<AudioSource>>,
@@ -37,8 +72,26 @@ pub struct BoomSounds {
 const ASTEROID_SIZE_TYPES_LEN: usize = 6;
 const ASTEROID_SIZE_TYPES: [&str; ASTEROID_SIZE_TYPES_LEN] = ["XS", "S", "M", "L", "XL", "XXL"];
 
+pub fn size_type(size: f32) -> &'static str {
+    ASTEROID_SIZE_TYPES[(size / (ASTEROID_SIZE_TYPES_LEN as f32)).round() as usize]
+}
+
+/// Collision material of a size type, the manifest default if it has none
+pub fn material(gameconfig: &GameConfig, size_type: &str) -> PhysicsMaterial {
+    PhysicsMaterial::from(
+        gameconfig
+            .asteroids
+            .materials
+            .get(size_type)
+            .unwrap_or(&gameconfig.physics.material),
+    )
+}
+
 const ANIMATION_DURATION: f32 = 0.5;
 
+// candidates tried before giving up on an out of view spawn
+const SPAWN_ATTEMPTS: usize = 8;
+
 const SUN_SIZE: f32 = 500.0;
 
 #[derive(Component)]
@@ -51,59 +104,158 @@ pub struct DespawnAnimation {
     timer: Timer,
 }
 
+impl Default for DespawnAnimation {
+    fn default() -> Self {
+        DespawnAnimation {
+            timer: Timer::from_seconds(ANIMATION_DURATION, TimerMode::Once),
+        }
+    }
+}
+
+/// Sun of `size` with its light, glow and gravity
+pub fn sun_bundle(
+    assets: &AsteroidAssets,
+    size: f32,
+    translation: Vec3,
+    velocity: Vec3,
+) -> impl Bundle {
+    let [sun_mesh, wireframe_mesh] = assets.sun_meshes.clone();
+    let [sun_material, sun_aura_material, wireframe_material] = assets.sun_materials.clone();
+    (
+        Sun { size },
+        GravitySource {
+            mass: size.powi(3),
+            radius: size,
+        },
+        Mesh3d(sun_mesh.clone()),
+        Transform {
+            translation,
+            scale: Vec3::splat(size),
+            ..default()
+        },
+        Velocity(velocity),
+        MeshMaterial3d(sun_material),
+        children![
+            (PointLight {
+                intensity: 2. * size * 1_000_000_000.0,
+                range: size * 100.0,
+                radius: size,
+                color: Color::WHITE,
+                shadows_enabled: true,
+                ..default()
+            },),
+            (
+                Mesh3d(wireframe_mesh), // wireframe
+                Transform {
+                    scale: Vec3::new(1.001, 1.001, 1.001),
+                    ..default()
+                },
+                MeshMaterial3d(wireframe_material),
+            ),
+            (
+                Mesh3d(sun_mesh), // slightly larger
+                Transform {
+                    scale: Vec3::new(1.1, 1.1, 1.1),
+                    ..default()
+                },
+                MeshMaterial3d(sun_aura_material),
+            )
+        ],
+    )
+}
+
 pub struct AsteroidPlugin;
 
 impl Plugin for AsteroidPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(Startup, setup)
+        app.add_message::<messages::AsteroidDestroyed>()
+            .add_message::<messages::ShipImpact>()
+            .add_message::<messages::SunContact>()
+            .add_message::<messages::AsteroidBounce>()
+            .add_systems(Startup, setup)
+            .add_systems(Startup, init_spawn_wave)
+            // every run starts back at the first wave of the sequence
+            .add_systems(OnExit(MenuState::GameOver), init_spawn_wave)
             .add_systems(
                 Update,
                 (
                     spawn::animate_spawn,
                     spawn::animate_despawn,
                     spawn::animate_despawn_sun,
+                    fragmentation::animate_debris,
+                    fragmentation::expire_shattered,
                 ),
             )
             .add_systems(
-                Update,
+                FixedUpdate,
+                // chained so shots skip the rocks the collisions already broke or merged
                 (
                     collision::asteroid_asteroid_collision,
                     collision::asteroid_ammo_collision,
-                    spawn::asteroid_wave,
-                    spawn::clear_asteroid,
                 )
-                    .run_if(in_state(GameState::Game)),
+                    .chain()
+                    .in_set(PhysicsSystems::Collide),
             )
             .add_systems(
-                Update,
+                FixedUpdate,
                 (
                     collision::asteroid_player_collision,
                     collision::sun_player_collision,
                 )
-                    .run_if(in_state(GameState::Game))
+                    .in_set(PhysicsSystems::Collide)
                     .run_if(in_state(GameOverState::None)),
+            )
+            .add_systems(
+                Update,
+                (
+                    effects::score_destroyed,
+                    effects::explode_destroyed,
+                    effects::bounce_sounds,
+                    patterns::next_wave,
+                    patterns::follow_origin.before(spawn::asteroid_wave),
+                    spawn::asteroid_wave,
+                    spawn::clear_asteroid,
+                    spawn::update_asteroid_lod,
+                )
+                    .run_if(in_state(GameState::Game)),
             );
     }
 }
 
+fn init_spawn_wave(mut commands: Commands, gameconfig: Res<GameConfig>) {
+    commands.insert_resource(patterns::SpawnWave::new(&gameconfig.asteroids.spawn));
+}
+
 pub fn setup(
     mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<StandardMaterial>>,
     asset_server: Res<AssetServer>,
     mut texture_atlases: ResMut<Assets<TextureAtlasLayout>>,
+    gameconfig: Res<GameConfig>,
 ) {
-    let mut asteroid_meshes: HashMap<String, Vec<Handle<Mesh>>> = Default::default();
+    let generator_config = &gameconfig.asteroids.generator;
+    let mut asteroid_meshes: HashMap<String, Vec<Vec<Handle<Mesh>>>> = Default::default();
     let mut asteroid_materials: HashMap<String, Handle<StandardMaterial>> = Default::default();
-    for asteroid_type in ASTEROID_SIZE_TYPES {
-        for n in 0..4 {
-            let mesh =
-                asset_server.load(format!("asteroids/{asteroid_type}{n}.glb#Mesh0/Primitive0"));
+    for (class, asteroid_type) in ASTEROID_SIZE_TYPES.iter().enumerate() {
+        for n in 0..generator_config.variants {
+            let lods = if generator_config.procedural {
+                generator::generate_lods(
+                    generator::variant_seed(generator_config.seed, class, n),
+                    generator_config,
+                )
+                .iter()
+                .map(|data| meshes.add(data.to_mesh()))
+                .collect()
+            } else {
+                vec![asset_server.load(format!("asteroids/{asteroid_type}{n}.glb#Mesh0/Primitive0"))]
+            };
             asteroid_meshes
                 .entry(asteroid_type.to_string()) // ensure key exists
                 .or_default()
-                .push(mesh);
+                .push(lods);
         }
-        let material = match asteroid_type {
+        let material = match *asteroid_type {
             "XS" => materials.add(StandardMaterial {
                 base_color: Color::srgb(0.5, 0.3, 0.6),
                 emissive: Color::srgb(0.7, 0.2, 0.2).to_linear() * 0.3,
@@ -129,7 +281,6 @@ pub fn setup(
     }
 
     let sun_translation = Vec3::new(-1000.0, 1000.0, 0.0);
-    let sun_scale = Vec3::new(SUN_SIZE, SUN_SIZE, SUN_SIZE);
 
     let sun_mesh = asset_server.load("Sun.glb#Mesh0/Primitive0");
     let sun_material = materials.add(StandardMaterial {
@@ -152,45 +303,8 @@ pub fn setup(
         unlit: true,
         ..Default::default()
     });
-    commands.spawn((
-        Sun { size: SUN_SIZE },
-        Mesh3d(sun_mesh.clone()),
-        Transform {
-            translation: sun_translation,
-            scale: sun_scale,
-            ..default()
-        },
-        Velocity(Vec3::ZERO),
-        MeshMaterial3d(sun_material.clone()),
-        children![
-            (PointLight {
-                intensity: 2. * SUN_SIZE * 1_000_000_000.0,
-                range: SUN_SIZE * 100.0,
-                radius: SUN_SIZE,
-                color: Color::WHITE,
-                shadows_enabled: true,
-                ..default()
-            },),
-            (
-                Mesh3d(wireframe_mesh.clone()), // wireframe
-                Transform {
-                    scale: Vec3::new(1.001, 1.001, 1.001),
-                    ..default()
-                },
-                MeshMaterial3d(wireframe_material.clone()),
-            ),
-            (
-                Mesh3d(sun_mesh.clone()), // slightly larger
-                Transform {
-                    scale: Vec3::new(1.1, 1.1, 1.1),
-                    ..default()
-                },
-                MeshMaterial3d(sun_aura_material.clone()),
-            )
-        ],
-    ));
 
-    commands.insert_resource(AsteroidAssets {
+    let assets = AsteroidAssets {
         meshes: asteroid_meshes,
         materials: asteroid_materials,
         explosion_sheet: asset_server.load("explosion_sheet.png"),
@@ -203,7 +317,9 @@ pub fn setup(
         )),
         sun_meshes: [sun_mesh, wireframe_mesh],
         sun_materials: [sun_material, sun_aura_material, wireframe_material],
-    });
+    };
+    commands.spawn(sun_bundle(&assets, SUN_SIZE, sun_translation, Vec3::ZERO));
+    commands.insert_resource(assets);
     commands.insert_resource(BoomSounds {
         booms: vec![
             asset_server.load("sounds/boom1.wav"),
@@ -0,0 +1,245 @@
+use rand::seq::IndexedRandom;
+use rand::Rng;
+
+use crate::asteroids::*;
+use crate::config::structs::SpawnConfig;
+use crate::floating_origin::OriginShifted;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SpawnPattern {
+    /// Uniformly on the spawn sphere, aimed at the player
+    Sphere,
+    /// Orbiting band around the closest sun, passing through the player
+    Belt,
+    /// Swarms sharing the same origin and heading
+    Cluster,
+    /// Circle around the player closing in
+    Ring,
+    /// Parallel flow crossing the player's position
+    Stream,
+}
+
+impl SpawnPattern {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "sphere" => Some(SpawnPattern::Sphere),
+            "belt" => Some(SpawnPattern::Belt),
+            "cluster" => Some(SpawnPattern::Cluster),
+            "ring" => Some(SpawnPattern::Ring),
+            "stream" => Some(SpawnPattern::Stream),
+            _ => None,
+        }
+    }
+}
+
+pub struct SpawnPoint {
+    pub position: Vec3,
+    pub direction: Vec3,
+    // how much the heading of each asteroid may deviate from `direction`
+    pub spread: f32,
+}
+
+/// Pattern used by `asteroid_wave` until the timer runs out
+#[derive(Resource)]
+pub struct SpawnWave {
+    pub pattern: SpawnPattern,
+    // ring normal or stream direction
+    pub axis: Vec3,
+    timer: Timer,
+    // waves started so far this run
+    number: usize,
+    cluster_center: Vec3,
+    cluster_left: usize,
+}
+
+impl SpawnWave {
+    pub fn new(config: &SpawnConfig) -> Self {
+        Self {
+            pattern: pick_pattern(config, 0, &mut rand::rng()),
+            axis: Vec3::Y,
+            timer: Timer::from_seconds(config.wave_duration, TimerMode::Repeating),
+            number: 0,
+            cluster_center: Vec3::ZERO,
+            cluster_left: 0,
+        }
+    }
+
+    /// Forget the current swarm, the next cluster spawn picks a new center
+    pub fn reset_cluster(&mut self) {
+        self.cluster_left = 0;
+    }
+
+    pub fn sample(
+        &mut self,
+        config: &SpawnConfig,
+        spawn_range: f32,
+        player: Vec3,
+        sun: Option<Vec3>,
+        rng: &mut impl Rng,
+    ) -> SpawnPoint {
+        match self.pattern {
+            SpawnPattern::Sphere => sphere(spawn_range, player, rng),
+            SpawnPattern::Belt => match sun {
+                Some(sun) => belt(config, spawn_range, player, sun, rng)
+                    .unwrap_or_else(|| sphere(spawn_range, player, rng)),
+                None => sphere(spawn_range, player, rng),
+            },
+            SpawnPattern::Cluster => {
+                if self.cluster_left == 0 {
+                    self.cluster_center = player + random_unit(rng) * spawn_range;
+                    self.cluster_left = config.cluster_size;
+                }
+                self.cluster_left -= 1;
+
+                SpawnPoint {
+                    position: self.cluster_center
+                        + random_unit(rng)
+                            * rng.random_range(0.0..config.cluster_radius.max(0.0) + 0.1),
+                    direction: (player - self.cluster_center).normalize(),
+                    spread: 0.05,
+                }
+            }
+            SpawnPattern::Ring => {
+                let (u, v) = self.axis.any_orthonormal_pair();
+                let angle = rng.random_range(0.0..std::f32::consts::TAU);
+                let offset = (u * angle.cos() + v * angle.sin()) * spawn_range;
+
+                SpawnPoint {
+                    position: player + offset,
+                    direction: -offset.normalize(),
+                    spread: 0.1,
+                }
+            }
+            SpawnPattern::Stream => {
+                let (u, v) = self.axis.any_orthonormal_pair();
+                let angle = rng.random_range(0.0..std::f32::consts::TAU);
+                // sqrt keeps the density uniform over the disc
+                let radius = config.stream_width * rng.random::<f32>().sqrt();
+
+                SpawnPoint {
+                    position: player - self.axis * spawn_range
+                        + (u * angle.cos() + v * angle.sin()) * radius,
+                    direction: self.axis,
+                    spread: 0.05,
+                }
+            }
+        }
+    }
+}
+
+/// Pattern of the wave `number` of a run: the manifest sequence first, then weighted picks
+pub fn pick_pattern(config: &SpawnConfig, number: usize, rng: &mut impl Rng) -> SpawnPattern {
+    if let Some(pattern) = config
+        .sequence
+        .get(number)
+        .and_then(|name| SpawnPattern::parse(name))
+    {
+        return pattern;
+    }
+
+    let patterns: Vec<(SpawnPattern, f32)> = config
+        .patterns
+        .iter()
+        .filter_map(|(name, weight)| SpawnPattern::parse(name).map(|p| (p, *weight)))
+        .collect();
+
+    patterns
+        .choose_weighted(rng, |(_, weight)| *weight)
+        .map(|(pattern, _)| *pattern)
+        .unwrap_or(SpawnPattern::Sphere)
+}
+
+fn random_unit(rng: &mut impl Rng) -> Vec3 {
+    let theta = rng.random_range(0.0..std::f32::consts::TAU);
+    let z: f32 = rng.random_range(-1.0..1.0);
+    let r = (1.0 - z * z).sqrt();
+
+    Vec3::new(r * theta.cos(), r * theta.sin(), z)
+}
+
+fn sphere(spawn_range: f32, player: Vec3, rng: &mut impl Rng) -> SpawnPoint {
+    let offset = random_unit(rng) * spawn_range;
+
+    SpawnPoint {
+        position: player + offset,
+        direction: -offset.normalize(),
+        spread: 0.3,
+    }
+}
+
+fn belt(
+    config: &SpawnConfig,
+    spawn_range: f32,
+    player: Vec3,
+    sun: Vec3,
+    rng: &mut impl Rng,
+) -> Option<SpawnPoint> {
+    let offset = player - sun;
+    let radial = Vec3::new(offset.x, 0.0, offset.z);
+    let orbit_radius = radial.length();
+    if orbit_radius < spawn_range {
+        return None;
+    }
+
+    // somewhere along the orbit ahead of or behind the player, never on top of it
+    let arc = rng.random_range(0.5..1.0) * spawn_range * if rng.random() { 1.0 } else { -1.0 };
+    let angle = radial.z.atan2(radial.x) + arc / orbit_radius;
+    let radius = orbit_radius + rng.random_range(-0.5..0.5) * config.belt_width;
+    let height = offset.y + rng.random_range(-0.125..0.125) * config.belt_width;
+
+    let position = sun + Vec3::new(angle.cos() * radius, height, angle.sin() * radius);
+    let tangent = Vec3::new(-angle.sin(), 0.0, angle.cos());
+
+    Some(SpawnPoint {
+        position,
+        direction: tangent,
+        spread: 0.1,
+    })
+}
+
+pub fn is_in_view(camera: &Camera, camera_transform: &GlobalTransform, position: Vec3) -> bool {
+    match camera.world_to_ndc(camera_transform, position) {
+        Some(ndc) => ndc.z > 0.0 && ndc.x.abs() <= 1.0 && ndc.y.abs() <= 1.0,
+        None => false,
+    }
+}
+
+/// Keeps the cluster center in place when the world is recentred
+pub fn follow_origin(mut shifted: MessageReader<OriginShifted>, mut wave: ResMut<SpawnWave>) {
+    for shift in shifted.read() {
+        wave.cluster_center -= shift.offset;
+    }
+}
+
+pub fn next_wave(time: Res<Time>, gameconfig: Res<GameConfig>, mut wave: ResMut<SpawnWave>) {
+    if !wave.timer.tick(time.delta()).just_finished() {
+        return;
+    }
+
+    let mut rng = rand::rng();
+    wave.number += 1;
+    wave.pattern = pick_pattern(&gameconfig.asteroids.spawn, wave.number, &mut rng);
+    wave.axis = random_unit(&mut rng);
+    wave.reset_cluster();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sequence_comes_before_the_weighted_picks() {
+        let config = SpawnConfig {
+            patterns: vec![("belt".to_string(), 1.0)],
+            sequence: vec!["ring".to_string(), "stream".to_string()],
+            ..Default::default()
+        };
+        let mut rng = rand::rng();
+
+        assert_eq!(pick_pattern(&config, 0, &mut rng), SpawnPattern::Ring);
+        assert_eq!(pick_pattern(&config, 1, &mut rng), SpawnPattern::Stream);
+        for number in 2..10 {
+            assert_eq!(pick_pattern(&config, number, &mut rng), SpawnPattern::Belt);
+        }
+    }
+}
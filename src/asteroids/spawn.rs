@@ -1,6 +1,7 @@
+use crate::asteroids::patterns::{is_in_view, SpawnWave};
 use crate::asteroids::utils::*;
 use crate::asteroids::*;
-use crate::controller::structs::Player;
+use crate::controller::structs::{Player, PlayerCam};
 use crate::physics::{RotationVelocity, Velocity};
 use rand::Rng;
 
@@ -9,7 +10,10 @@ pub fn asteroid_wave(
     gameconfig: Res<crate::config::structs::GameConfig>,
     query: Query<Entity, With<Asteroid>>,
     assets: Res<AsteroidAssets>,
+    mut wave: ResMut<SpawnWave>,
     player: Single<&Transform, With<Player>>,
+    camera: Single<(&Camera, &GlobalTransform), With<PlayerCam>>,
+    sun_query: Query<&Transform, (With<Sun>, Without<DespawnAnimation>)>,
 ) {
     let config = gameconfig.asteroids.clone();
 
@@ -19,16 +23,38 @@ pub fn asteroid_wave(
     }
 
     let mut rng = rand::rng();
+    let (camera, camera_transform) = *camera;
+    let sun = sun_query.iter().map(|tr| tr.translation).min_by(|a, b| {
+        a.distance_squared(player.translation)
+            .total_cmp(&b.distance_squared(player.translation))
+    });
 
     let to_spawn = config.max_asteroid - current;
     for _ in 0..to_spawn {
-        let theta = rng.random_range(0.0..std::f32::consts::TAU);
-        let phi = rng.random_range(0.0..std::f32::consts::PI);
-
-        let x = config.spawn_range * phi.sin() * theta.cos();
-        let y = config.spawn_range * phi.cos();
-        let z = config.spawn_range * phi.sin() * theta.sin();
-        let position = Vec3::new(x, y, z) + player.translation;
+        let attempts = if config.spawn.avoid_view {
+            SPAWN_ATTEMPTS
+        } else {
+            1
+        };
+        let Some(spawn) = (0..attempts)
+            .map(|_| {
+                wave.sample(
+                    &config.spawn,
+                    config.spawn_range,
+                    player.translation,
+                    sun,
+                    &mut rng,
+                )
+            })
+            .find(|spawn| {
+                !config.spawn.avoid_view || !is_in_view(camera, camera_transform, spawn.position)
+            })
+        else {
+            // every candidate was on screen, try again next frame
+            wave.reset_cluster();
+            continue;
+        };
+        let position = spawn.position;
 
         let random_dir = Vec3::new(
             rng.random_range(-1.0..1.0),
@@ -51,7 +77,7 @@ pub fn asteroid_wave(
         let rounded_size = size.round();
 
         let velocity =
-            -(position.normalize() + random_dir * 0.3).normalize() * f(size) * config.speed;
+            (spawn.direction + random_dir * spawn.spread).normalize() * f(size) * config.speed;
         let rotation_velocity = Vec3::new(
             rng.random_range(-1.0..1.0),
             rng.random_range(-1.0..1.0),
@@ -60,8 +86,10 @@ pub fn asteroid_wave(
         .normalize()
             * f(size)
             * config.rotationnal_speed;
+        let (mesh, lod) = assets.random_mesh(size_type, &mut rng);
         commands.spawn((
-            Mesh3d(assets.meshes.get(size_type).unwrap()[rng.random_range(0..4)].clone()),
+            mesh,
+            lod,
             MeshMaterial3d(assets.materials.get(size_type).unwrap().clone()),
             Asteroid { size: rounded_size },
             Transform {
@@ -72,6 +100,7 @@ pub fn asteroid_wave(
             },
             Velocity(velocity),
             RotationVelocity(rotation_velocity),
+            material(&gameconfig, size_type),
             SpawnAnimation {
                 timer: Timer::from_seconds(ANIMATION_DURATION, TimerMode::Once),
             },
@@ -149,3 +178,35 @@ pub fn clear_asteroid(
         }
     }
 }
+
+pub fn update_asteroid_lod(
+    gameconfig: Res<crate::config::structs::GameConfig>,
+    assets: Res<AsteroidAssets>,
+    player: Single<&Transform, With<Player>>,
+    mut query: Query<(&Asteroid, &Transform, &mut AsteroidLod, &mut Mesh3d)>,
+) {
+    let lod_distance = gameconfig.asteroids.generator.lod_distance;
+    if lod_distance <= 0.0 {
+        return;
+    }
+
+    for (asteroid, transform, mut lod, mut mesh) in &mut query {
+        let Some(lods) = assets
+            .meshes
+            .get(size_type(asteroid.size))
+            .and_then(|variants| variants.get(lod.variant))
+        else {
+            continue;
+        };
+
+        // bigger rocks keep their details further away
+        let distance = transform.translation.distance(player.translation);
+        let level =
+            ((distance / (lod_distance * asteroid.size.max(1.0))) as usize).min(lods.len() - 1);
+
+        if level != lod.level {
+            lod.level = level;
+            mesh.0 = lods[level].clone();
+        }
+    }
+}
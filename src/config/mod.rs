@@ -4,6 +4,9 @@ use xml::reader::{EventReader, XmlEvent};
 
 pub mod structs;
 
+/// `assets/asteroids/{type}{n}.glb` files shipped for every size type
+pub const SHIPPED_VARIANTS: usize = 5;
+
 pub fn load_game_config(path: &str) -> structs::GameConfig {
     let file = File::open(path).expect("Cannot open XML file");
     let parser = EventReader::new(file);
@@ -23,10 +26,51 @@ pub fn load_game_config(path: &str) -> structs::GameConfig {
                         let v = parse_value(&attributes);
                         match scope_path(&scope).as_str() {
                             "game/ship/speed/value" => cfg.ship.speed = v,
-                            "game/ship/gun/ammo/speed/value" => cfg.ship.ammo.speed = v,
+                            "game/asteroids/hit_points/value" => cfg.asteroids.hit_points = v,
+                            path if path.starts_with("game/ship/gun/weapons/") => {
+                                if let [_, _, _, _, name, field, _] =
+                                    path.split('/').collect::<Vec<_>>()[..]
+                                {
+                                    let weapon = weapon_config(&mut cfg.ship.weapons, name);
+                                    match field {
+                                        "speed" => weapon.speed = v,
+                                        "damage" => weapon.damage = v,
+                                        "radius" => weapon.radius = v,
+                                        "spread_count" => weapon.spread_count = v as usize,
+                                        "spread_angle" => weapon.spread_angle = v,
+                                        "fire_rate" => weapon.fire_rate = v,
+                                        "heat" => weapon.heat = v,
+                                        "magazine" => weapon.magazine = v as usize,
+                                        "reserve" => weapon.reserve = v as usize,
+                                        "reload_time" => weapon.reload_time = v,
+                                        "range" => weapon.range = v,
+                                        _ => {}
+                                    }
+                                }
+                            }
                             "game/ship/gun/ammo/despawn_distance/value" => {
                                 cfg.ship.ammo.distance_despawn = v
                             }
+                            "game/ship/gun/ammo/max_lights/value" => {
+                                cfg.ship.ammo.max_lights = v as usize
+                            }
+                            "game/ship/gun/ammo/pool_size/value" => {
+                                cfg.ship.ammo.pool_size = v as usize
+                            }
+                            "game/ship/gun/heat/cooling/value" => cfg.ship.heat.cooling = v,
+                            "game/ship/gun/heat/recover/value" => cfg.ship.heat.recover = v,
+                            "game/ship/gun/missiles/lock_time/value" => {
+                                cfg.ship.missiles.lock_time = v
+                            }
+                            "game/ship/gun/missiles/lock_range/value" => {
+                                cfg.ship.missiles.lock_range = v
+                            }
+                            "game/ship/gun/missiles/turn_rate/value" => {
+                                cfg.ship.missiles.turn_rate = v
+                            }
+                            "game/ship/gun/aim/assist/value" => cfg.ship.aim.assist = v != 0.0,
+                            "game/ship/gun/aim/assist_cone/value" => cfg.ship.aim.assist_cone = v,
+                            "game/ship/gun/aim/target_cone/value" => cfg.ship.aim.target_cone = v,
                             "game/ship/camera/transition/value" => {
                                 cfg.main_cam.speed_transition = v
                             }
@@ -44,11 +88,211 @@ pub fn load_game_config(path: &str) -> structs::GameConfig {
                             }
                             "game/asteroids/size_range/min/value" => cfg.asteroids.size_range.0 = v,
                             "game/asteroids/size_range/max/value" => cfg.asteroids.size_range.1 = v,
+                            "game/asteroids/generator/procedural/value" => {
+                                cfg.asteroids.generator.procedural = v != 0.0
+                            }
+                            "game/asteroids/generator/seed/value" => {
+                                cfg.asteroids.generator.seed = v as u64
+                            }
+                            "game/asteroids/generator/variants/value" => {
+                                cfg.asteroids.generator.variants = v as usize
+                            }
+                            "game/asteroids/generator/subdivisions/value" => {
+                                cfg.asteroids.generator.subdivisions = v as u32
+                            }
+                            "game/asteroids/generator/lod_levels/value" => {
+                                cfg.asteroids.generator.lod_levels = v as u32
+                            }
+                            "game/asteroids/generator/lod_distance/value" => {
+                                cfg.asteroids.generator.lod_distance = v
+                            }
+                            "game/asteroids/generator/strength/value" => {
+                                cfg.asteroids.generator.strength = v
+                            }
+                            "game/asteroids/generator/frequency/value" => {
+                                cfg.asteroids.generator.frequency = v
+                            }
                             "game/ship/virtual_mouse_sensitivity/value" => {
                                 cfg.ship.virtual_mouse_sensitivity = v
                             }
                             "game/ship/rotation_speed/value" => cfg.ship.rotation_speed = v,
+                            "game/ship/damage/hull/value" => cfg.ship.damage.hull = v,
+                            "game/ship/damage/shield/value" => cfg.ship.damage.shield = v,
+                            "game/ship/damage/shield_regen/value" => {
+                                cfg.ship.damage.shield_regen = v
+                            }
+                            "game/ship/damage/regen_delay/value" => cfg.ship.damage.regen_delay = v,
+                            "game/ship/damage/impulse_threshold/value" => {
+                                cfg.ship.damage.impulse_threshold = v
+                            }
+                            "game/ship/damage/damage_per_impulse/value" => {
+                                cfg.ship.damage.damage_per_impulse = v
+                            }
+                            "game/ship/damage/sun_multiplier/value" => {
+                                cfg.ship.damage.sun_multiplier = v
+                            }
                             "game/ship/thurst_modifier/value" => cfg.ship.thurst_modifier = v,
+                            "game/asteroids/spawn/wave_duration/value" => {
+                                cfg.asteroids.spawn.wave_duration = v
+                            }
+                            "game/asteroids/spawn/belt_width/value" => {
+                                cfg.asteroids.spawn.belt_width = v
+                            }
+                            "game/asteroids/spawn/cluster_size/value" => {
+                                cfg.asteroids.spawn.cluster_size = v as usize
+                            }
+                            "game/asteroids/spawn/cluster_radius/value" => {
+                                cfg.asteroids.spawn.cluster_radius = v
+                            }
+                            "game/asteroids/spawn/stream_width/value" => {
+                                cfg.asteroids.spawn.stream_width = v
+                            }
+                            "game/asteroids/spawn/avoid_view/value" => {
+                                cfg.asteroids.spawn.avoid_view = v != 0.0
+                            }
+                            // game/asteroids/spawn/patterns/{pattern}/value
+                            path if path.starts_with("game/asteroids/spawn/patterns/") => {
+                                if let [_, _, _, _, pattern, _] =
+                                    path.split('/').collect::<Vec<_>>()[..]
+                                {
+                                    cfg.asteroids.spawn.patterns.push((pattern.to_string(), v));
+                                }
+                            }
+                            "game/asteroids/fragmentation/min_fragments/value" => {
+                                cfg.asteroids.fragmentation.min_fragments = v as usize
+                            }
+                            "game/asteroids/fragmentation/max_fragments/value" => {
+                                cfg.asteroids.fragmentation.max_fragments = v as usize
+                            }
+                            "game/asteroids/fragmentation/distribution/value" => {
+                                cfg.asteroids.fragmentation.distribution = v
+                            }
+                            "game/asteroids/fragmentation/min_size/value" => {
+                                cfg.asteroids.fragmentation.min_size = v
+                            }
+                            "game/asteroids/fragmentation/ejection_speed/value" => {
+                                cfg.asteroids.fragmentation.ejection_speed = v
+                            }
+                            "game/asteroids/fragmentation/ammo_mass/value" => {
+                                cfg.asteroids.fragmentation.ammo_mass = v
+                            }
+                            "game/asteroids/fragmentation/debris_lifetime/value" => {
+                                cfg.asteroids.fragmentation.debris_lifetime = v
+                            }
+                            "game/physics/tick_rate/value" => cfg.physics.tick_rate = v,
+                            "game/physics/recenter_distance/value" => {
+                                cfg.physics.recenter_distance = v
+                            }
+                            "game/physics/material/restitution/value" => {
+                                cfg.physics.material.restitution = v
+                            }
+                            "game/physics/material/friction/value" => {
+                                cfg.physics.material.friction = v
+                            }
+                            "game/ship/material/restitution/value" => {
+                                cfg.ship.material.restitution = v
+                            }
+                            "game/ship/material/friction/value" => cfg.ship.material.friction = v,
+                            "game/asteroids/impacts/shatter_energy/value" => {
+                                cfg.asteroids.impacts.shatter_energy = v
+                            }
+                            "game/asteroids/impacts/merge_speed/value" => {
+                                cfg.asteroids.impacts.merge_speed = v
+                            }
+                            "game/asteroids/impacts/merge_max_size/value" => {
+                                cfg.asteroids.impacts.merge_max_size = v
+                            }
+                            "game/asteroids/impacts/chain_duration/value" => {
+                                cfg.asteroids.impacts.chain_duration = v
+                            }
+                            path if path.starts_with("game/powerups/") => {
+                                if let [_, _, name, field, _] =
+                                    path.split('/').collect::<Vec<_>>()[..]
+                                {
+                                    let powerup = cfg.powerups.entry(name.to_string()).or_default();
+                                    match field {
+                                        "duration" => powerup.duration = v,
+                                        "strength" => powerup.strength = v,
+                                        "angle" => powerup.angle = v,
+                                        "max_stacks" => powerup.max_stacks = v as u32,
+                                        "max_duration" => powerup.max_duration = v,
+                                        _ => {}
+                                    }
+                                }
+                            }
+                            "game/ship/hitboxes/fit_count/value" => {
+                                cfg.ship.hitboxes.fit_count = v as usize
+                            }
+                            path if path.starts_with("game/ship/hitboxes/") => {
+                                if let (Some(shape), &[_, _, _, _, "radius", _]) = (
+                                    cfg.ship.hitboxes.shapes.last_mut(),
+                                    &path.split('/').collect::<Vec<_>>()[..],
+                                ) {
+                                    shape.radius = v
+                                }
+                            }
+                            path if path.starts_with("game/shop/") => {
+                                if let [_, _, name, field, _] =
+                                    path.split('/').collect::<Vec<_>>()[..]
+                                {
+                                    let upgrade =
+                                        cfg.shop.upgrades.entry(name.to_string()).or_default();
+                                    match field {
+                                        "cost" => upgrade.cost = v,
+                                        "cost_growth" => upgrade.cost_growth = v,
+                                        "bonus" => upgrade.bonus = v,
+                                        "max_level" => upgrade.max_level = v as u32,
+                                        _ => {}
+                                    }
+                                }
+                            }
+                            path if path.starts_with("game/asteroids/materials/") => {
+                                if let [_, _, _, size_type, field, _] =
+                                    path.split('/').collect::<Vec<_>>()[..]
+                                {
+                                    let material = cfg
+                                        .asteroids
+                                        .materials
+                                        .entry(size_type.to_string())
+                                        .or_default();
+                                    match field {
+                                        "restitution" => material.restitution = v,
+                                        "friction" => material.friction = v,
+                                        _ => {}
+                                    }
+                                }
+                            }
+                            "game/physics/interpolation/value" => {
+                                cfg.physics.interpolation = v != 0.0
+                            }
+                            "game/gravity/enabled/value" => cfg.gravity.enabled = v != 0.0,
+                            "game/gravity/strength/value" => cfg.gravity.strength = v,
+                            "game/gravity/max_acceleration/value" => {
+                                cfg.gravity.max_acceleration = v
+                            }
+                            "game/gravity/trajectory/steps/value" => {
+                                cfg.gravity.trajectory_steps = v as usize
+                            }
+                            "game/gravity/trajectory/step/value" => cfg.gravity.trajectory_step = v,
+                            "game/pickups/radius/value" => cfg.pickups.radius = v,
+                            "game/pickups/lifetime/value" => cfg.pickups.lifetime = v,
+                            "game/pickups/ore_value/value" => cfg.pickups.ore_value = v,
+                            "game/pickups/mining_rolls/value" => {
+                                cfg.pickups.mining_rolls = v as usize
+                            }
+                            "game/pickups/ammo_refill/value" => cfg.pickups.ammo_refill = v,
+                            // game/pickups/drops/{size type}/{pickup kind}/value
+                            path if path.starts_with("game/pickups/drops/") => {
+                                if let [_, _, _, size_type, kind, _] =
+                                    path.split('/').collect::<Vec<_>>()[..]
+                                {
+                                    cfg.pickups.drops.push(structs::DropConfig {
+                                        size_type: size_type.to_string(),
+                                        kind: kind.to_string(),
+                                        probability: v,
+                                    });
+                                }
+                            }
                             _ => {}
                         }
                     }
@@ -93,7 +337,30 @@ pub fn load_game_config(path: &str) -> structs::GameConfig {
                             "game/ship/thruster/right/vec3" => cfg.ship.thruster_right = v,
                             "game/ship/thruster/left/vec3" => cfg.ship.thruster_left = v,
 
-                            "game/ship/gun/ammo/color/vec3" => cfg.ship.ammo.color = v,
+                            path if path.starts_with("game/ship/gun/weapons/")
+                                && path.ends_with("/color/vec3") =>
+                            {
+                                if let [_, _, _, _, name, _, _] =
+                                    path.split('/').collect::<Vec<_>>()[..]
+                                {
+                                    weapon_config(&mut cfg.ship.weapons, name).color = v;
+                                }
+                            }
+                            "game/ship/gun/missiles/trail_color/vec3" => {
+                                cfg.ship.missiles.trail_color = v
+                            }
+                            path if path.starts_with("game/ship/hitboxes/") => {
+                                if let (Some(shape), &[_, _, _, _, field, _]) = (
+                                    cfg.ship.hitboxes.shapes.last_mut(),
+                                    &path.split('/').collect::<Vec<_>>()[..],
+                                ) {
+                                    match field {
+                                        "center" => shape.center = v,
+                                        "half_axis" => shape.half_axis = v,
+                                        _ => {}
+                                    }
+                                }
+                            }
                             "game/ship/gun/right/vec3" => cfg.ship.gun_right = v,
                             "game/ship/gun/left/vec3" => cfg.ship.gun_left = v,
 
@@ -125,17 +392,40 @@ pub fn load_game_config(path: &str) -> structs::GameConfig {
                                 "game/ui/background/asset" => cfg.ui.background = path,
                                 "game/ui/font/asset" => cfg.ui.font = path,
                                 "game/ui/sounds/asset" => cfg.ui.sounds.push(path),
-                                "game/ship/gun/ammo/sounds/asset" => {
-                                    cfg.ship.ammo.sounds.push(path)
+                                weapon_path
+                                    if weapon_path.starts_with("game/ship/gun/weapons/") =>
+                                {
+                                    if let [_, _, _, _, name, "sounds", _] =
+                                        weapon_path.split('/').collect::<Vec<_>>()[..]
+                                    {
+                                        weapon_config(&mut cfg.ship.weapons, name).sounds.push(path)
+                                    }
                                 }
                                 "game/ship/music/asset" => cfg.ship.music = path,
                                 "game/ship/alarm/asset" => cfg.ship.alarm = path,
+                                "game/ship/gun/ammo/empty/asset" => {
+                                    cfg.ship.ammo.empty_sound = path
+                                }
+                                "game/ship/gun/heat/overheat/asset" => {
+                                    cfg.ship.heat.overheat_sound = path
+                                }
                                 "game/ui/music/asset" => cfg.ui.music = path,
+                                "game/pickups/sound/asset" => cfg.pickups.sound = path,
                                 _ => {}
                             }
                         }
                     }
 
+                    "sphere" | "capsule"
+                        if scope.len() == 4
+                            && scope_path(&scope).starts_with("game/ship/hitboxes/") =>
+                    {
+                        cfg.ship
+                            .hitboxes
+                            .shapes
+                            .push(structs::HitboxConfig::default());
+                    }
+
                     _ => {}
                 }
             }
@@ -148,6 +438,37 @@ pub fn load_game_config(path: &str) -> structs::GameConfig {
                         _ => {}
                     }
                 }
+                if let [_, "asteroids", "spawn", "sequence", "wave"] =
+                    scope.iter().map(String::as_str).collect::<Vec<_>>()[..]
+                {
+                    cfg.asteroids.spawn.sequence.push(s.trim().to_string());
+                }
+                if let [_, _, _, "weapons", name, field] =
+                    scope.iter().map(String::as_str).collect::<Vec<_>>()[..]
+                {
+                    let weapon = weapon_config(&mut cfg.ship.weapons, name);
+                    match field {
+                        "projectile" => weapon.projectile = s.trim().to_string(),
+                        "pattern" => weapon.pattern = s.trim().to_string(),
+                        _ => {}
+                    }
+                }
+                if let [_, "powerups", name, "stacking"] =
+                    scope.iter().map(String::as_str).collect::<Vec<_>>()[..]
+                {
+                    cfg.powerups.entry(name.to_string()).or_default().stacking =
+                        s.trim().to_string();
+                }
+                if let [_, "ship", "hitboxes", "auto_fit"] =
+                    scope.iter().map(String::as_str).collect::<Vec<_>>()[..]
+                {
+                    cfg.ship.hitboxes.auto_fit = s.trim().to_string();
+                }
+                if let [_, "shop", "save"] =
+                    scope.iter().map(String::as_str).collect::<Vec<_>>()[..]
+                {
+                    cfg.shop.save = s.trim().to_string();
+                }
             }
 
             Ok(XmlEvent::EndElement { .. }) => {
@@ -168,9 +489,137 @@ pub fn load_game_config(path: &str) -> structs::GameConfig {
         cfg.window.y = 600.0;
     }
 
+    // Same amount of variants as the shipped glb files, which are all there is to load
+    let generator = &mut cfg.asteroids.generator;
+    if generator.variants == 0 {
+        generator.variants = SHIPPED_VARIANTS;
+    }
+    if !generator.procedural {
+        generator.variants = generator.variants.min(SHIPPED_VARIANTS);
+    }
+
+    if cfg.asteroids.spawn.wave_duration <= 0.0 {
+        cfg.asteroids.spawn.wave_duration = 30.0;
+    }
+    if cfg.asteroids.spawn.cluster_size == 0 {
+        cfg.asteroids.spawn.cluster_size = 1;
+    }
+
+    let fragmentation = &mut cfg.asteroids.fragmentation;
+    fragmentation.min_fragments = fragmentation.min_fragments.max(2);
+    fragmentation.max_fragments = fragmentation.max_fragments.max(fragmentation.min_fragments);
+    if fragmentation.distribution <= 0.0 {
+        fragmentation.distribution = 1.0;
+    }
+    if fragmentation.debris_lifetime <= 0.0 {
+        fragmentation.debris_lifetime = 1.0;
+    }
+
+    if cfg.ship.damage.hull <= 0.0 {
+        cfg.ship.damage.hull = 100.0;
+    }
+
+    // the ship always has something to shoot with
+    if cfg.ship.weapons.is_empty() {
+        weapon_config(&mut cfg.ship.weapons, "laser");
+    }
+    for weapon in cfg.ship.weapons.iter_mut() {
+        if weapon.radius <= 0.0 {
+            weapon.radius = 1.0;
+        }
+        if weapon.speed <= 0.0 {
+            weapon.speed = 50.0;
+        }
+        weapon.spread_count = weapon.spread_count.max(1);
+        if weapon.fire_rate <= 0.0 {
+            weapon.fire_rate = 4.0;
+        }
+        weapon.heat = weapon.heat.max(0.0);
+        weapon.magazine = weapon.magazine.max(1);
+        if weapon.range <= 0.0 {
+            weapon.range = 60.0;
+        }
+    }
+    if cfg.ship.heat.cooling <= 0.0 {
+        cfg.ship.heat.cooling = 25.0;
+    }
+    cfg.ship.heat.recover = cfg.ship.heat.recover.clamp(0.0, 100.0);
+    for powerup in cfg.powerups.values_mut() {
+        powerup.max_stacks = powerup.max_stacks.max(1);
+        if powerup.max_duration < powerup.duration {
+            powerup.max_duration = powerup.duration;
+        }
+    }
+
+    // the hitboxes the ship always had, should the manifest have none
+    cfg.ship.hitboxes.shapes.retain(|shape| shape.radius > 0.0);
+    if cfg.ship.hitboxes.shapes.is_empty() {
+        cfg.ship.hitboxes.shapes = [(-0.85, 0.75, 0.4), (0.1, 0.9, 0.7), (1.3, 1.0, 0.4)]
+            .into_iter()
+            .map(|(z, y, radius)| structs::HitboxConfig {
+                center: Vec3::new(0.0, y, z),
+                radius,
+                half_axis: Vec3::ZERO,
+            })
+            .collect();
+    }
+    if cfg.ship.hitboxes.auto_fit.is_empty() {
+        cfg.ship.hitboxes.auto_fit = "none".to_string();
+    }
+    cfg.ship.hitboxes.fit_count = cfg.ship.hitboxes.fit_count.max(1);
+    cfg.pickups.mining_rolls = cfg.pickups.mining_rolls.max(1);
+
+    for upgrade in cfg.shop.upgrades.values_mut() {
+        upgrade.cost = upgrade.cost.max(0.0);
+        if upgrade.cost_growth < 1.0 {
+            upgrade.cost_growth = 1.0;
+        }
+    }
+
+    if cfg.ship.missiles.lock_range <= 0.0 {
+        cfg.ship.missiles.lock_range = cfg.asteroids.spawn_range;
+    }
+
+    cfg.ship.aim.assist_cone = cfg.ship.aim.assist_cone.clamp(0.0, 90.0);
+    cfg.ship.aim.target_cone = cfg.ship.aim.target_cone.clamp(0.0, 90.0);
+
+    if cfg.physics.tick_rate <= 0.0 {
+        cfg.physics.tick_rate = 64.0;
+    }
+
+    if cfg.asteroids.impacts.shatter_energy <= 0.0 {
+        cfg.asteroids.impacts.shatter_energy = f32::MAX;
+    }
+
+    if cfg.physics.recenter_distance <= 0.0 {
+        cfg.physics.recenter_distance = 1000.0;
+    }
+
+    if cfg.gravity.max_acceleration <= 0.0 {
+        cfg.gravity.max_acceleration = f32::MAX;
+    }
+
     cfg
 }
 
+/// Weapon `name` of the manifest, added on first sight so the order is kept
+fn weapon_config<'a>(
+    weapons: &'a mut Vec<structs::WeaponConfig>,
+    name: &str,
+) -> &'a mut structs::WeaponConfig {
+    let index = match weapons.iter().position(|weapon| weapon.name == name) {
+        Some(index) => index,
+        None => {
+            weapons.push(structs::WeaponConfig {
+                name: name.to_string(),
+                ..Default::default()
+            });
+            weapons.len() - 1
+        }
+    };
+    &mut weapons[index]
+}
+
 fn parse_vec2(attrs: &[xml::attribute::OwnedAttribute]) -> Vec2 {
     Vec2 {
         x: find_attr(attrs, "x").unwrap_or("0").parse().unwrap_or(0.0),
@@ -1,3 +1,5 @@
+use std::collections::HashMap;
+
 use bevy::prelude::{Resource, Vec2, Vec3};
 
 #[derive(Debug, Clone, Default, Resource)]
@@ -9,6 +11,93 @@ pub struct GameConfig {
     pub ui: UIConfig,
     pub ship: ShipConfig,
     pub asteroids: AsteroidConfig,
+    pub pickups: PickupConfig,
+    // keyed by power-up name, they drop through the pickup tables
+    pub powerups: HashMap<String, PowerUpConfig>,
+    pub shop: ShopConfig,
+    pub gravity: GravityConfig,
+    pub physics: PhysicsConfig,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ShopConfig {
+    // credits and upgrade levels are kept there between runs, nothing is saved if empty
+    pub save: String,
+    // keyed by upgrade name
+    pub upgrades: HashMap<String, UpgradeConfig>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct UpgradeConfig {
+    // price of the first level, each next one costs `cost_growth` times more
+    pub cost: f32,
+    pub cost_growth: f32,
+    // fraction of the base stat added per level
+    pub bonus: f32,
+    pub max_level: u32,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct PhysicsConfig {
+    // fixed ticks per second
+    pub tick_rate: f32,
+    pub interpolation: bool,
+    // used by the bodies without a material of their own
+    pub material: MaterialConfig,
+    // distance from the origin at which the world is recentred on the ship
+    pub recenter_distance: f32,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct MaterialConfig {
+    // share of the approach speed given back along the normal, 0 is a dead stop
+    pub restitution: f32,
+    // tangential impulse as a fraction of the normal one, makes grazing hits spin
+    pub friction: f32,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct GravityConfig {
+    pub enabled: bool,
+    pub strength: f32,
+    pub max_acceleration: f32,
+    // HUD prediction, number of integration steps and their duration
+    pub trajectory_steps: usize,
+    pub trajectory_step: f32,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct PickupConfig {
+    pub radius: f32,
+    pub lifetime: f32,
+    pub ore_value: f32,
+    // times the ore and fuel drops are rolled for an asteroid mined by the beam
+    pub mining_rolls: usize,
+    // part of each weapon reserve given back by an ammo pickup
+    pub ammo_refill: f32,
+    pub sound: String,
+    pub drops: Vec<DropConfig>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct PowerUpConfig {
+    // seconds
+    pub duration: f32,
+    // meaning depends on the power-up
+    pub strength: f32,
+    // degrees, spread shot only
+    pub angle: f32,
+    // refresh, extend, intensity
+    pub stacking: String,
+    pub max_stacks: u32,
+    pub max_duration: f32,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct DropConfig {
+    pub size_type: String,
+    pub kind: String,
+    pub probability: f32,
 }
 
 #[derive(Debug, Clone, Default)]
@@ -19,6 +108,65 @@ pub struct AsteroidConfig {
     pub despawn_range: f32,
     pub speed: f32,
     pub rotationnal_speed: f32,
+    pub generator: AsteroidGeneratorConfig,
+    pub spawn: SpawnConfig,
+    pub fragmentation: FragmentationConfig,
+    // size type -> material
+    pub materials: HashMap<String, MaterialConfig>,
+    pub impacts: ImpactConfig,
+    // damage an asteroid takes per unit of size before breaking
+    pub hit_points: f32,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ImpactConfig {
+    // impact energy per unit of mass of the smaller asteroid above which it shatters
+    pub shatter_energy: f32,
+    // slower touches merge the two rocks, 0 disables merging
+    pub merge_speed: f32,
+    // only rocks up to this size merge
+    pub merge_max_size: f32,
+    // seconds during which fragments can't merge and their breakups score for the player
+    pub chain_duration: f32,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct FragmentationConfig {
+    pub min_fragments: usize,
+    pub max_fragments: usize,
+    // exponent applied to the random volume shares, higher gives more uneven pieces
+    pub distribution: f32,
+    // fragments smaller than this become debris
+    pub min_size: f32,
+    pub ejection_speed: f32,
+    pub ammo_mass: f32,
+    pub debris_lifetime: f32,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct SpawnConfig {
+    pub wave_duration: f32,
+    // pattern name and weight of being picked for a wave
+    pub patterns: Vec<(String, f32)>,
+    // pattern of each of the first waves of a run, in order
+    pub sequence: Vec<String>,
+    pub belt_width: f32,
+    pub cluster_size: usize,
+    pub cluster_radius: f32,
+    pub stream_width: f32,
+    pub avoid_view: bool,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct AsteroidGeneratorConfig {
+    pub procedural: bool,
+    pub seed: u64,
+    pub variants: usize,
+    pub subdivisions: u32,
+    pub lod_levels: u32,
+    pub lod_distance: f32,
+    pub strength: f32,
+    pub frequency: f32,
 }
 
 #[derive(Debug, Clone, Default)]
@@ -47,12 +195,91 @@ pub struct UIConfig {
 
 #[derive(Debug, Clone, Default)]
 pub struct AmmoConfig {
-    pub speed: f32,
     pub distance_despawn: f32,
+    // clicked when firing with an empty magazine
+    pub empty_sound: String,
+    // projectiles in flight with a point light, the others are emissive only
+    pub max_lights: usize,
+    // projectiles spawned up front
+    pub pool_size: usize,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct WeaponConfig {
+    pub name: String,
+    // laser, plasma, missile, beam
+    pub projectile: String,
+    // alternate, simultaneous, spread
+    pub pattern: String,
+    pub speed: f32,
+    pub damage: f32,
+    pub radius: f32,
     pub color: Vec3,
+    pub spread_count: usize,
+    // degrees
+    pub spread_angle: f32,
+    // trigger pulls per second while the button is held
+    pub fire_rate: f32,
+    // heat added per trigger pull, the gauge holds 100. Per second for a beam,
+    // which also deals `damage` per second
+    pub heat: f32,
+    // reach of a beam
+    pub range: f32,
+    // trigger pulls per magazine, and spare ones carried
+    pub magazine: usize,
+    pub reserve: usize,
+    // seconds
+    pub reload_time: f32,
     pub sounds: Vec<String>,
 }
 
+#[derive(Debug, Clone, Default)]
+pub struct MissileConfig {
+    // seconds the reticle stays on an asteroid to lock it
+    pub lock_time: f32,
+    pub lock_range: f32,
+    // degrees per second
+    pub turn_rate: f32,
+    pub trail_color: Vec3,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct AimConfig {
+    // initial state of the aim assist option
+    pub assist: bool,
+    // degrees, shots are bent toward the lead point when it is this close
+    pub assist_cone: f32,
+    // degrees around the reticle an asteroid is picked for the lead marker
+    pub target_cone: f32,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct HeatConfig {
+    // heat lost per second
+    pub cooling: f32,
+    // overheated guns unlock once the gauge is back under this level
+    pub recover: f32,
+    pub overheat_sound: String,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct HitboxConfig {
+    // ship space
+    pub center: Vec3,
+    pub radius: f32,
+    // half of the capsule segment in ship space, zero for a sphere
+    pub half_axis: Vec3,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct HitboxesConfig {
+    // "none" keeps `shapes`, "spheres" or "capsule" derive them from the ship model once loaded
+    pub auto_fit: String,
+    // spheres strung along the model by the "spheres" fit
+    pub fit_count: usize,
+    pub shapes: Vec<HitboxConfig>,
+}
+
 #[derive(Debug, Clone, Default)]
 pub struct ShipConfig {
     pub music: String,
@@ -69,10 +296,31 @@ pub struct ShipConfig {
     pub gun_right: Vec3,
     pub gun_left: Vec3,
     pub ammo: AmmoConfig,
+    // cycled in this order, the first one is selected at start
+    pub weapons: Vec<WeaponConfig>,
+    pub heat: HeatConfig,
+    pub missiles: MissileConfig,
+    pub aim: AimConfig,
+    pub hitboxes: HitboxesConfig,
     pub screen_right: ScreenQuad,
     pub screen_center: ScreenQuad,
     pub screen_left: ScreenQuad,
     pub color_particules: (Vec3, Vec3),
+    pub damage: DamageConfig,
+    pub material: MaterialConfig,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct DamageConfig {
+    pub hull: f32,
+    pub shield: f32,
+    // shield points per second, once `regen_delay` seconds passed without a hit
+    pub shield_regen: f32,
+    pub regen_delay: f32,
+    // impulses under the threshold are harmless scrapes
+    pub impulse_threshold: f32,
+    pub damage_per_impulse: f32,
+    pub sun_multiplier: f32,
 }
 
 #[derive(Debug, Clone, Default)]
@@ -6,6 +6,7 @@ use crate::game_states::GameState;
 use crate::globals_structs::Keybinds;
 use crate::menu::structs::SmoothCamMove;
 use crate::physics::{RotationVelocity, Velocity};
+use crate::player::aim::LeadMarker;
 use bevy::asset::{AssetServer, Handle};
 use bevy::input::mouse::AccumulatedMouseMotion;
 use bevy::input::ButtonInput;
@@ -161,7 +162,6 @@ pub fn move_player_system(
         }
         velocity.0 = (velocity.0 + speed_to_add).clamp_length_max(20.);
     } else {
-        
         if velocity.0.length_squared() > 100. {
             if velocity.0.length_squared() > (velocity.0 + speed_to_add).length_squared() {
                 velocity.0 += speed_to_add;
@@ -249,13 +249,14 @@ pub fn rotate_spaceship(
 
 pub fn roll_spaceship(
     time: Res<Time>,
-    mut player_rot: Single<&mut RotationVelocity, With<Player>>,
+    player: Single<(&Transform, &mut RotationVelocity), With<Player>>,
     keybinds: Res<Keybinds>,
     keyboard: Res<ButtonInput<KeyCode>>,
     mouse: Res<ButtonInput<MouseButton>>,
 ) {
     let base_speed = 100.0_f32.to_radians(); // ≈3.49 rad/s
     let dt = time.delta_secs();
+    let (transform, mut player_rot) = player.into_inner();
 
     let mut accel_roll = 0.0;
 
@@ -266,15 +267,19 @@ pub fn roll_spaceship(
         accel_roll -= base_speed;
     }
 
+    // RotationVelocity is in world space, roll is around the ship own z axis
+    let mut local_rot = transform.rotation.inverse() * **player_rot;
+
     // Apply input acceleration to angular velocity
-    player_rot.z += accel_roll * dt;
+    local_rot.z += accel_roll * dt;
 
     const DAMPING: f32 = 0.99f32; // 1.0 = no damping
-    if player_rot.z.abs() < 2.0 {
-        player_rot.z *= DAMPING;
+    if local_rot.z.abs() < 2.0 {
+        local_rot.z *= DAMPING;
     }
 
-    player_rot.z = player_rot.z.clamp(-5.0, 5.0);
+    local_rot.z = local_rot.z.clamp(-5.0, 5.0);
+    **player_rot = transform.rotation * local_rot;
 }
 
 pub fn setup_ui(
@@ -348,6 +353,19 @@ pub fn setup_ui(
                     ..default()
                 },
                 BorderRadius::all(Val::Percent(50.0)),
+            ),
+            (
+                Node {
+                    width: Val::Px(20.0),
+                    height: Val::Px(20.0),
+                    position_type: PositionType::Absolute,
+                    border: UiRect::all(Val::Px(2.0)),
+                    ..default()
+                },
+                BorderRadius::all(Val::Percent(50.0)),
+                BorderColor::all(Color::srgb(1.0, 0.6, 0.1)),
+                Visibility::Hidden,
+                LeadMarker,
             )
         ],
     ));
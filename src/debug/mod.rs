@@ -0,0 +1,160 @@
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+
+use crate::asteroids::{Asteroid, Sun};
+use crate::config::structs::GameConfig;
+use crate::controller::structs::{Player, PlayerCam, VirtualMouse};
+use crate::distancemetric::systems::closest_hit;
+use crate::game_states::GameState;
+use crate::globals_structs::Keybinds;
+use crate::physics::{Parked, RotationVelocity, Velocity};
+use crate::player::PlayerHitBox;
+
+/// Physics debug overlay, toggled with `Keybinds::debug`
+#[derive(Resource, Default)]
+pub struct DebugOverlay {
+    pub enabled: bool,
+}
+
+pub fn plugin(app: &mut App) {
+    app.init_resource::<DebugOverlay>();
+    app.add_systems(Update, toggle_overlay);
+    app.add_systems(
+        Update,
+        (
+            draw_colliders,
+            draw_velocities,
+            draw_ranges,
+            draw_metric_ray,
+        )
+            .run_if(in_state(GameState::Game))
+            .run_if(|overlay: Res<DebugOverlay>| overlay.enabled),
+    );
+}
+
+fn toggle_overlay(
+    keybinds: Res<Keybinds>,
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mouse: Res<ButtonInput<MouseButton>>,
+    mut overlay: ResMut<DebugOverlay>,
+) {
+    if keybinds.debug.just_pressed(&keyboard, &mouse) {
+        overlay.enabled = !overlay.enabled;
+    }
+}
+
+fn draw_colliders(
+    mut gizmos: Gizmos,
+    player: Single<&Transform, With<Player>>,
+    player_hitboxes: Query<(&Transform, &PlayerHitBox), Without<Player>>,
+    asteroids: Query<(&Transform, &Asteroid), Without<Player>>,
+    suns: Query<(&Transform, &Sun), Without<Player>>,
+) {
+    for (hb_transform, hitbox) in &player_hitboxes {
+        // where the collision systems put it
+        let center = player.transform_point(hb_transform.translation);
+        let axis = player.rotation * hitbox.half_axis;
+        let color = Color::srgb(0.0, 1.0, 0.0);
+        if axis == Vec3::ZERO {
+            gizmos.sphere(center, hitbox.radius, color);
+        } else {
+            gizmos.primitive_3d(
+                &Capsule3d::new(hitbox.radius, 2.0 * axis.length()),
+                Isometry3d::new(center, Quat::from_rotation_arc(Vec3::Y, axis.normalize())),
+                color,
+            );
+        }
+    }
+    for (transform, asteroid) in &asteroids {
+        gizmos.sphere(
+            transform.translation,
+            asteroid.size,
+            Color::srgb(1.0, 0.5, 0.0),
+        );
+    }
+    for (transform, sun) in &suns {
+        gizmos
+            .sphere(transform.translation, sun.size, Color::srgb(1.0, 1.0, 0.0))
+            .resolution(128);
+    }
+}
+
+fn draw_velocities(
+    mut gizmos: Gizmos,
+    bodies: Query<(&Transform, &Velocity, Option<&RotationVelocity>), Without<Parked>>,
+) {
+    for (transform, velocity, rotation_velocity) in &bodies {
+        if velocity.length_squared() > 0.0 {
+            gizmos.arrow(
+                transform.translation,
+                transform.translation + **velocity,
+                Color::srgb(0.0, 1.0, 1.0),
+            );
+        }
+        if let Some(rotation_velocity) = rotation_velocity {
+            // spin axis, length in rad/s
+            gizmos.ray(
+                transform.translation,
+                **rotation_velocity,
+                Color::srgb(1.0, 0.0, 1.0),
+            );
+        }
+    }
+}
+
+fn draw_ranges(
+    mut gizmos: Gizmos,
+    gameconfig: Res<GameConfig>,
+    player: Single<&Transform, With<Player>>,
+) {
+    let config = &gameconfig.asteroids;
+    gizmos
+        .sphere(
+            player.translation,
+            config.spawn_range,
+            Color::srgba(0.0, 1.0, 0.0, 0.3),
+        )
+        .resolution(64);
+    gizmos
+        .sphere(
+            player.translation,
+            config.despawn_range,
+            Color::srgba(1.0, 0.0, 0.0, 0.3),
+        )
+        .resolution(64);
+}
+
+/// Same ray as `get_distance_of_object`, yellow up to the hit or red when it misses
+fn draw_metric_ray(
+    mut gizmos: Gizmos,
+    gameconfig: Res<GameConfig>,
+    camera: Single<(&GlobalTransform, &Camera), With<PlayerCam>>,
+    mouse: Single<&VirtualMouse>,
+    window: Single<&Window, With<PrimaryWindow>>,
+    asteroids: Query<(&GlobalTransform, &Asteroid)>,
+) {
+    let (cam_transform, camera) = *camera;
+    let window_dimension = Vec2::new(window.width() / 2., window.height() / 2.);
+    let Ok(ray) = camera.viewport_to_world(cam_transform, mouse.pos + window_dimension) else {
+        return;
+    };
+
+    let ray_origin = cam_transform.transform_point(Vec3::ZERO);
+    let ray_dir = ray.direction.normalize();
+    let closest = closest_hit(
+        asteroids
+            .iter()
+            .map(|(transform, asteroid)| (transform.translation(), asteroid.size)),
+        ray_origin,
+        ray_dir,
+    );
+
+    match closest {
+        Some(distance) => gizmos.ray(ray_origin, ray_dir * distance, Color::srgb(1.0, 1.0, 0.0)),
+        None => gizmos.ray(
+            ray_origin,
+            ray_dir * gameconfig.asteroids.spawn_range,
+            Color::srgb(1.0, 0.0, 0.0),
+        ),
+    }
+}
@@ -2,7 +2,7 @@ use bevy::prelude::*;
 
 mod setup;
 pub mod structs;
-mod systems;
+pub mod systems;
 
 pub fn plugin(app: &mut App) {
     app.add_systems(Startup, setup::setup_texture_camera);
@@ -10,5 +10,13 @@ pub fn plugin(app: &mut App) {
         PostStartup,
         (setup::setup_metric_screen, setup::apply_texture_to_quad),
     );
-    app.add_systems(Update, systems::get_distance_of_object);
+    app.add_systems(
+        Update,
+        (
+            systems::get_distance_of_object,
+            systems::show_weapon,
+            systems::show_ammo,
+            systems::show_heat,
+        ),
+    );
 }
@@ -75,6 +75,55 @@ pub fn setup_metric_screen(
                     ..default()
                 },
             ));
+
+            parent
+                .spawn(Node {
+                    column_gap: Val::Px(40.0),
+                    ..default()
+                })
+                .with_children(|row| {
+                    row.spawn((
+                        Text::new(""),
+                        TextFont {
+                            font: font.clone(),
+                            font_size: 80.0,
+                            ..default()
+                        },
+                        TextColor(Color::srgb(1.0, 0.75, 0.2)),
+                        WeaponText,
+                    ));
+                    row.spawn((
+                        Text::new(""),
+                        TextFont {
+                            font: font.clone(),
+                            font_size: 80.0,
+                            ..default()
+                        },
+                        TextColor(Color::srgb(0.0, 1.0, 1.0)),
+                        AmmoText,
+                    ));
+                });
+
+            parent
+                .spawn((
+                    Node {
+                        width: Val::Percent(70.0),
+                        height: Val::Px(30.0),
+                        margin: UiRect::top(Val::Px(20.0)),
+                        border: UiRect::all(Val::Px(3.0)),
+                        ..default()
+                    },
+                    BorderColor::all(Color::srgb(0.0, 1.0, 1.0)),
+                ))
+                .with_child((
+                    Node {
+                        width: Val::Percent(0.0),
+                        height: Val::Percent(100.0),
+                        ..default()
+                    },
+                    BackgroundColor(Color::srgb(0.0, 1.0, 1.0)),
+                    HeatBar,
+                ));
         });
 }
 
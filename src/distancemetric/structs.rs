@@ -17,5 +17,16 @@ pub struct MetricText;
 #[derive(Component)]
 pub struct SpeedText;
 
+#[derive(Component)]
+pub struct WeaponText;
+
+/// Magazine and reserve of the current weapon
+#[derive(Component)]
+pub struct AmmoText;
+
+/// Filled part of the gun heat gauge
+#[derive(Component)]
+pub struct HeatBar;
+
 #[derive(Resource)]
 pub struct DistanceTimer(pub Timer);
@@ -4,6 +4,8 @@ use crate::{
     controller::structs::{Player, PlayerCam, VirtualMouse},
     distancemetric::structs::MetricText,
     physics::Velocity,
+    player::missiles::LockOn,
+    player::weapons::{Arsenal, GunHeat, ProjectileKind, MAX_HEAT},
 };
 use bevy::prelude::*;
 use bevy::window::PrimaryWindow;
@@ -29,6 +31,45 @@ fn hit_sphere(center: Vec3, radius: f32, ray_pos: Vec3, ray_dir: Vec3) -> f32 {
     }
 }
 
+/// Distance along the ray to the closest of the `(center, radius)` spheres
+pub fn closest_hit(
+    spheres: impl IntoIterator<Item = (Vec3, f32)>,
+    ray_origin: Vec3,
+    ray_dir: Vec3,
+) -> Option<f32> {
+    closest_target(
+        spheres
+            .into_iter()
+            .map(|(center, radius)| ((), center, radius)),
+        ray_origin,
+        ray_dir,
+    )
+    .map(|(_, distance)| distance)
+}
+
+/// Same as `closest_hit`, keeping which of the `(key, center, radius)` spheres was hit
+pub fn closest_target<K>(
+    spheres: impl IntoIterator<Item = (K, Vec3, f32)>,
+    ray_origin: Vec3,
+    ray_dir: Vec3,
+) -> Option<(K, f32)> {
+    let mut closest: Option<(K, f32)> = None;
+
+    for (key, obj_pos, radius) in spheres {
+        let t = hit_sphere(obj_pos, radius, ray_origin, ray_dir);
+
+        if t > 0.0
+            && closest
+                .as_ref()
+                .is_none_or(|(_, closest_val)| *closest_val > t)
+        {
+            closest = Some((key, t));
+        }
+    }
+
+    closest
+}
+
 pub fn get_distance_of_object(
     asteroids: Query<(&GlobalTransform, &Asteroid)>,
     mut cameras: ParamSet<(
@@ -69,26 +110,82 @@ pub fn get_distance_of_object(
     let ray_origin = cam_transform.transform_point(Vec3::ZERO);
     let ray_dir = ray.direction.normalize();
 
-    let mut closest: Option<f32> = None;
+    let closest = closest_hit(
+        asteroids
+            .iter()
+            .map(|(transform, asteroid)| (transform.translation(), asteroid.size)),
+        ray_origin,
+        ray_dir,
+    );
 
-    for (transform, asteroid) in &asteroids {
-        let obj_pos = transform.translation();
-        let radius = asteroid.size;
-        let t = hit_sphere(obj_pos, radius, ray_origin, ray_dir);
+    text.p0().0 = match closest {
+        Some(d) => format!("{:.0}m", d),
+        None => "--m".into(),
+    };
+}
+
+pub fn show_weapon(
+    arsenal: Res<Arsenal>,
+    lock: Res<LockOn>,
+    mut text: Single<&mut Text, With<WeaponText>>,
+) {
+    if !(arsenal.is_changed() || lock.is_changed() || text.0.is_empty()) {
+        return;
+    }
 
-        if t > 0.0 {
-            if let Some(closest_val) = closest {
-                if closest_val > t {
-                    closest = Some(t);
-                }
-            } else {
-                closest = Some(t);
-            }
+    let weapon = arsenal.weapon();
+    let label = match weapon.projectile {
+        ProjectileKind::Missile if lock.target.is_some() => {
+            format!("{} LOCK", weapon.name.to_uppercase())
         }
+        _ => weapon.name.to_uppercase(),
+    };
+    if text.0 != label {
+        text.0 = label;
     }
+}
 
-    text.p0().0 = match closest {
-        Some(d) => format!("{:.0}m", d),
-        None => "--m".into(),
+pub fn show_ammo(arsenal: Res<Arsenal>, text: Single<(&mut Text, &mut TextColor), With<AmmoText>>) {
+    if !arsenal.is_changed() {
+        return;
+    }
+
+    let (mut text, mut color) = text.into_inner();
+    let weapon = arsenal.weapon();
+    let (label, warning) = if weapon.projectile == ProjectileKind::Beam {
+        ("--".to_string(), false)
+    } else if arsenal.reload.is_some() {
+        ("RELOAD".to_string(), true)
+    } else {
+        (
+            format!("{}/{}", weapon.rounds, weapon.reserve),
+            weapon.rounds == 0,
+        )
+    };
+    if text.0 != label {
+        text.0 = label;
+        color.0 = if warning {
+            Color::srgb(1.0, 0.1, 0.1)
+        } else {
+            Color::srgb(0.0, 1.0, 1.0)
+        };
+    }
+}
+
+pub fn show_heat(
+    heat: Res<GunHeat>,
+    bar: Single<(&mut Node, &mut BackgroundColor), With<HeatBar>>,
+) {
+    if !heat.is_changed() {
+        return;
+    }
+
+    let (mut node, mut color) = bar.into_inner();
+    node.width = Val::Percent(heat.value / MAX_HEAT * 100.0);
+    color.0 = if heat.overheated {
+        Color::srgb(1.0, 0.1, 0.1)
+    } else {
+        // cyan to orange as it heats up
+        Color::srgb(0.0, 1.0, 1.0).mix(&Color::srgb(1.0, 0.6, 0.0), heat.value / MAX_HEAT)
     };
 }
@@ -0,0 +1,107 @@
+use bevy::math::DVec3;
+use bevy::prelude::*;
+
+use crate::config::structs::GameConfig;
+use crate::controller::structs::Player;
+use crate::game_states::GameState;
+use crate::menu::structs::MenuState;
+use crate::physics::PhysicsState;
+
+/// Where the current origin sits in the world, kept in f64 so long runs add up exactly
+#[derive(Resource, Default)]
+pub struct FloatingOrigin {
+    pub offset: DVec3,
+}
+
+impl FloatingOrigin {
+    /// Position in the world of `translation`, which is relative to the current origin
+    pub fn world_position(&self, translation: Vec3) -> DVec3 {
+        self.offset + translation.as_dvec3()
+    }
+}
+
+/// Where the current run started, and the farthest the ship got from there
+#[derive(Resource, Default)]
+pub struct Voyage {
+    pub launch: DVec3,
+    pub farthest: f64,
+}
+
+/// The whole world was moved by `-offset`, world positions kept outside of `Transform` must follow
+#[derive(Message, Debug, Clone)]
+pub struct OriginShifted {
+    pub offset: Vec3,
+}
+
+pub fn plugin(app: &mut App) {
+    app.init_resource::<FloatingOrigin>()
+        .init_resource::<Voyage>()
+        .add_message::<OriginShifted>()
+        .add_systems(Update, track_voyage.run_if(in_state(GameState::Game)))
+        .add_systems(OnExit(MenuState::GameOver), start_voyage)
+        .add_systems(
+            PostUpdate,
+            recenter
+                .before(TransformSystems::Propagate)
+                .run_if(in_state(GameState::Game)),
+        );
+}
+
+/// Moves every root entity so the ship is back at the origin once it went too far,
+/// children follow their parent
+pub fn recenter(
+    gameconfig: Res<GameConfig>,
+    mut origin: ResMut<FloatingOrigin>,
+    mut shifted: MessageWriter<OriginShifted>,
+    player: Single<(&mut Transform, Option<&mut PhysicsState>), With<Player>>,
+    mut roots: Query<
+        (&mut Transform, Option<&mut PhysicsState>),
+        (Without<ChildOf>, Without<Player>),
+    >,
+) {
+    let (mut player_transform, player_state) = player.into_inner();
+    let offset = player_transform.translation;
+    if offset.length() < gameconfig.physics.recenter_distance {
+        return;
+    }
+
+    shift(&mut player_transform, player_state, offset);
+    for (mut transform, state) in &mut roots {
+        shift(&mut transform, state, offset);
+    }
+
+    origin.offset += offset.as_dvec3();
+    debug!("origin moved by {offset}, now at {}", origin.offset);
+    shifted.write(OriginShifted { offset });
+}
+
+fn track_voyage(
+    origin: Res<FloatingOrigin>,
+    mut voyage: ResMut<Voyage>,
+    player: Single<&Transform, With<Player>>,
+) {
+    let distance = origin
+        .world_position(player.translation)
+        .distance(voyage.launch);
+    if distance > voyage.farthest {
+        voyage.farthest = distance;
+    }
+}
+
+fn start_voyage(
+    origin: Res<FloatingOrigin>,
+    mut voyage: ResMut<Voyage>,
+    player: Single<&Transform, With<Player>>,
+) {
+    *voyage = Voyage {
+        launch: origin.world_position(player.translation),
+        farthest: 0.0,
+    };
+}
+
+fn shift(transform: &mut Transform, state: Option<Mut<PhysicsState>>, offset: Vec3) {
+    transform.translation -= offset;
+    if let Some(mut state) = state {
+        state.shift(offset);
+    }
+}
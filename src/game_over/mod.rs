@@ -1,12 +1,19 @@
 use bevy::prelude::*;
 
 use crate::{
+    config::structs::GameConfig,
     controller::structs::{ControllerState, Player},
     game_over::drift::setup_drift,
     game_states::GameState,
     globals_structs::Score,
     menu::{structs::MenuState, systems::focus_main_screen},
     physics::{RotationVelocity, Velocity},
+    pickups::PickupInventory,
+    player::{
+        health::ShipHealth,
+        weapons::{Arsenal, GunHeat},
+    },
+    powerups::PowerUps,
 };
 
 mod drift;
@@ -126,11 +133,22 @@ fn blink_lights(time: Res<Time>, mut lights: Query<(&mut PointLight, &mut Blinki
 fn reset_gameover_state(
     mut next_gameover_state: ResMut<NextState<GameOverState>>,
     mut score: ResMut<Score>,
-    mut player: Single<(&mut Velocity, &mut RotationVelocity), With<Player>>,
+    mut inventory: ResMut<PickupInventory>,
+    mut arsenal: ResMut<Arsenal>,
+    mut heat: ResMut<GunHeat>,
+    mut powerups: ResMut<PowerUps>,
+    gameconfig: Res<GameConfig>,
+    mut player: Single<(&mut Velocity, &mut RotationVelocity, &mut ShipHealth), With<Player>>,
 ) {
     next_gameover_state.set(GameOverState::None);
 
     score.value = 0;
+    inventory.clear();
+    arsenal.restock();
+    powerups.active.clear();
+    heat.value = 0.0;
+    heat.overheated = false;
     **player.0 = Vec3::ZERO;
     **player.1 = Vec3::ZERO;
+    *player.2 = ShipHealth::new(&gameconfig.ship.damage);
 }
@@ -70,6 +70,9 @@ pub struct Keybinds {
     pub free_look: InputButton,
     pub shoot: InputButton,
     pub boost: InputButton,
+    pub debug: InputButton,
+    pub cycle_weapon: InputButton,
+    pub reload: InputButton,
 }
 
 impl Default for Keybinds {
@@ -91,6 +94,9 @@ impl Default for Keybinds {
             free_look: InputButton::Mouse(MouseButton::Right),
             shoot: InputButton::Mouse(MouseButton::Left),
             boost: InputButton::Key(KeyCode::ShiftLeft),
+            debug: InputButton::Key(KeyCode::F3),
+            cycle_weapon: InputButton::Key(KeyCode::Tab),
+            reload: InputButton::Key(KeyCode::KeyR),
         }
     }
 }
@@ -109,6 +115,9 @@ pub enum Action {
     Shoot,
     Menu,
     Boost,
+    Debug,
+    CycleWeapon,
+    Reload,
 }
 
 #[derive(Resource, Default)]
@@ -116,6 +125,12 @@ pub struct Score {
     pub value: u32,
 }
 
+impl Score {
+    pub fn add(&mut self, points: u32, multiplier: f32) {
+        self.value += (points as f32 * multiplier).round() as u32;
+    }
+}
+
 #[derive(Resource)]
 pub struct UIRessources {
     pub bg: Handle<Image>,
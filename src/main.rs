@@ -10,7 +10,9 @@ mod back_camera;
 mod background_musics;
 mod config;
 mod controller;
+mod debug;
 mod distancemetric;
+mod floating_origin;
 mod game_over;
 mod game_states;
 mod globals_structs;
@@ -18,22 +20,32 @@ mod helpers;
 mod menu;
 mod particules;
 mod physics;
+mod pickups;
 mod player;
+mod powerups;
 mod score_display;
+mod shop;
 mod skybox;
 mod spritesheet;
+#[cfg(test)]
+mod tests;
 
 use bevy_hanabi::HanabiPlugin;
 use bevy_sprite3d::Sprite3dPlugin;
 use game_states::GameState;
 use globals_structs::*;
 
-use crate::physics::{RotationVelocity, Velocity};
-use crate::player::PlayerHitBox;
+use crate::physics::{PhysicsMaterial, RotationVelocity, Velocity};
 
 fn main() {
     let gameconfig = config::load_game_config("assets/manifest.xml");
 
+    let args: Vec<String> = std::env::args().collect();
+    if args.get(1).map(String::as_str) == Some("generate-asteroids") {
+        asteroids::export::run_cli(&gameconfig, &args[2..]);
+        return;
+    }
+
     let width = if gameconfig.window.x > 0.0 {
         gameconfig.window.x as u32
     } else {
@@ -82,6 +94,13 @@ fn main() {
         background_musics::BackgroundMusicPlugin,
         game_over::GameOverPlugin,
         physics::PhysicsPlugin,
+        pickups::PickupPlugin,
+    ))
+    .add_plugins((
+        floating_origin::plugin,
+        debug::plugin,
+        powerups::plugin,
+        shop::plugin,
     ))
     .init_state::<GameState>()
     .insert_resource(MusicVolume { volume: 100.0_f32 })
@@ -242,25 +261,14 @@ fn setup(
         .spawn((
             SceneRoot(asset_server.load(gameconfig.ship.asset.clone())),
             controller::structs::Player,
+            player::health::ShipHealth::new(&gameconfig.ship.damage),
+            PhysicsMaterial::from(&gameconfig.ship.material),
             Velocity(Vec3::default()),
             RotationVelocity(Vec3::default()),
             Transform::default(),
-            children![
-                (
-                    PlayerHitBox { radius: 0.4 },
-                    Transform::from_xyz(0.0, 0.75, -0.85),
-                ),
-                (
-                    PlayerHitBox { radius: 0.7 },
-                    Transform::from_xyz(0.0, 0.9, 0.1),
-                ),
-                (
-                    PlayerHitBox { radius: 0.4 },
-                    Transform::from_xyz(0.0, 1.0, 1.3),
-                ),
-            ],
         ))
         .id();
+    player::hitbox::spawn_hitboxes(&mut commands, player_entity, &gameconfig);
 
     let camera_entity = commands
         .spawn((
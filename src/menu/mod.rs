@@ -38,6 +38,7 @@ pub fn menu_plugin(app: &mut App) {
     // Init the scene after entering into a specific menu state
     app.add_systems(OnEnter(MenuState::Main), create_main_menu_scene);
     app.add_systems(OnEnter(MenuState::Options), create_options_menu_scene);
+    app.add_systems(OnEnter(MenuState::Shop), create_shop_menu_scene);
     app.add_systems(OnEnter(MenuState::GameOver), create_gameover_menu_scene);
 
     app.add_systems(
@@ -1,7 +1,11 @@
+use crate::config::structs::{GameConfig, ShopConfig};
+use crate::floating_origin::Voyage;
 use crate::game_states::GameState;
 use crate::globals_structs::{Action, Keybinds, MusicVolume};
 use crate::globals_structs::{Score, UIRessources};
 use crate::menu::structs::*;
+use crate::player::aim::AimAssist;
+use crate::shop::{save_upgrades, UpgradeKind, Upgrades};
 use bevy::audio::Volume;
 use bevy::prelude::*;
 
@@ -26,6 +30,22 @@ static BORDER_RADIUS_SQUARE: BorderRadius = BorderRadius {
     bottom_right: Val::Px(4.0),
 };
 
+fn on_off(enabled: bool) -> &'static str {
+    if enabled {
+        "ON"
+    } else {
+        "OFF"
+    }
+}
+
+fn upgrade_status(upgrades: &Upgrades, kind: UpgradeKind, shop: &ShopConfig) -> String {
+    let max_level = crate::shop::upgrade_config(shop, kind).max_level;
+    match upgrades.price(kind, shop) {
+        Some(price) => format!("LV {}/{}  {} $", upgrades.level(kind), max_level, price),
+        None => format!("LV {}/{}  MAX", upgrades.level(kind), max_level),
+    }
+}
+
 fn default_node() -> Node {
     Node {
         width: Val::Percent(60.),
@@ -125,6 +145,23 @@ pub fn create_main_menu_scene(
                         next_state.set(MenuState::Options);
                     });
 
+                    parent.spawn((
+                        default_node(),
+                        BORDER_NORMAL,
+                        BackgroundColor(Color::srgba(0.3, 0.25, 0.0, 0.8)), // dark yellow transparent
+                        children![(
+                            Text::new("UPGRADE SHOP"),
+                            TextFont { font: font.clone(), font_size: 32.0, ..default() },
+                            TextColor(Color::srgb(1.0, 0.85, 0.0)),
+                        )],
+                    )).observe(|over: On<Pointer<Over>>, mut colors: Query<&mut BorderColor>| {
+                        *(colors.get_mut(over.entity).unwrap()) = BORDER_HOVER;
+                    }).observe(|out: On<Pointer<Out>>, mut colors: Query<&mut BorderColor>| {
+                        *(colors.get_mut(out.entity).unwrap()) = BORDER_NORMAL;
+                    }).observe(|_: On<Pointer<Click>>, mut next_state: ResMut<NextState<MenuState>>| {
+                        next_state.set(MenuState::Shop);
+                    });
+
                     parent
                         .spawn((
                             default_node(),
@@ -162,6 +199,7 @@ pub fn create_options_menu_scene(
     camera_components: Single<(Entity, &mut Camera), With<MenuCameraComponent>>,
     menu_ressources: Res<UIRessources>,
     master_volume: Res<MusicVolume>,
+    aim_assist: Res<AimAssist>,
     keybinds: Res<Keybinds>,
 ) {
     let (cam_entity, mut camera) = camera_components.into_inner();
@@ -262,6 +300,39 @@ pub fn create_options_menu_scene(
                         }
                     });
 
+                    // === Aim Assist ===
+                    content.spawn((
+                        Node {
+                            width: Val::Percent(90.),
+                            height: Val::Px(150.),
+                            justify_content: JustifyContent::SpaceBetween,
+                            align_items: AlignItems::Center,
+                            border: UiRect::all(Val::Px(2.0)),
+                            ..default()
+                        },
+                        BorderColor::all(Color::NONE),
+                        ButtonInfo { border_hover: BORDER_HOVER, border_normal: BorderColor::all(Color::NONE) },
+                        BORDER_RADIUS_SQUARE,
+                        children![
+                            (
+                                Text::new("Aim Assist"),
+                                TextFont { font: font.clone(), font_size: 52.0, ..default() },
+                                TextColor(Color::WHITE),
+                            ),
+                            (
+                                Text::new(on_off(aim_assist.enabled)),
+                                TextFont { font: font.clone(), font_size: 52.0, ..default() },
+                                TextColor(Color::srgb(0.0, 1.0, 0.0)),
+                                AimAssistText
+                            )
+                        ],
+                    )).observe(|_: On<Pointer<Click>>, mut aim_assist: ResMut<AimAssist>, mut texts: Query<&mut Text, With<AimAssistText>>| {
+                        aim_assist.enabled = !aim_assist.enabled;
+                        for mut text in &mut texts {
+                            *text = Text::new(on_off(aim_assist.enabled));
+                        }
+                    });
+
                     // === 4 Key Binds ===
                     let binds = [
                         ("Up", keybinds.up, Action::Up),
@@ -276,6 +347,9 @@ pub fn create_options_menu_scene(
                         ("Shoot", keybinds.shoot, Action::Shoot),
                         ("Menu", keybinds.menu, Action::Menu),
                         ("Boost", keybinds.boost, Action::Boost),
+                        ("Debug", keybinds.debug, Action::Debug),
+                        ("Weapon", keybinds.cycle_weapon, Action::CycleWeapon),
+                        ("Reload", keybinds.reload, Action::Reload),
                     ];
 
                     for (label, key, action) in binds {
@@ -341,11 +415,219 @@ pub fn create_options_menu_scene(
     });
 }
 
+pub fn create_shop_menu_scene(
+    mut commands: Commands,
+    camera_components: Single<(Entity, &mut Camera), With<MenuCameraComponent>>,
+    menu_ressources: Res<UIRessources>,
+    gameconfig: Res<GameConfig>,
+    upgrades: Res<Upgrades>,
+) {
+    let (cam_entity, mut camera) = camera_components.into_inner();
+    camera.is_active = true;
+
+    let font = menu_ressources.font.clone();
+    let background = menu_ressources.bg.clone();
+
+    commands
+        .spawn((
+            DespawnOnExit(MenuState::Shop),
+            Node {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                flex_direction: FlexDirection::Column,
+                ..default()
+            },
+            ImageNode {
+                image: background,
+                ..default()
+            },
+            UiTargetCamera(cam_entity),
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                Text::new("UPGRADE SHOP"),
+                TextFont {
+                    font: font.clone(),
+                    font_size: 50.0,
+                    ..default()
+                },
+                TextColor(Color::srgb(1.0, 0.85, 0.0)),
+                Node {
+                    margin: UiRect::new(Val::Px(0.0), Val::Px(0.0), Val::Px(30.0), Val::Px(10.0)),
+                    ..default()
+                },
+            ));
+            parent.spawn((
+                Text::new(format!("{} $", upgrades.credits)),
+                TextFont {
+                    font: font.clone(),
+                    font_size: 40.0,
+                    ..default()
+                },
+                TextColor(Color::srgb(0.0, 1.0, 1.0)),
+                Node {
+                    margin: UiRect::bottom(Val::Px(20.0)),
+                    ..default()
+                },
+                CreditsText,
+            ));
+            parent.spawn((
+                Text::new("Upgrades are fitted at the next launch"),
+                TextFont {
+                    font: font.clone(),
+                    font_size: 24.0,
+                    ..default()
+                },
+                TextColor(Color::srgba(1.0, 1.0, 1.0, 0.6)),
+                Node {
+                    margin: UiRect::bottom(Val::Px(20.0)),
+                    ..default()
+                },
+            ));
+
+            parent
+                .spawn((
+                    Node {
+                        align_self: AlignSelf::Stretch,
+                        height: percent(45),
+                        overflow: Overflow::scroll_y(),
+                        flex_direction: FlexDirection::Column,
+                        ..default()
+                    },
+                    BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.3)),
+                ))
+                .with_children(|scroll_root| {
+                    scroll_root
+                        .spawn((Node {
+                            width: Val::Percent(100.0),
+                            height: Val::Auto,
+                            flex_direction: FlexDirection::Column,
+                            justify_content: JustifyContent::Start,
+                            align_items: AlignItems::Center,
+                            row_gap: Val::Px(30.0),
+                            padding: UiRect::all(Val::Px(20.0)),
+                            ..default()
+                        },))
+                        .with_children(|content| {
+                            for kind in UpgradeKind::ALL {
+                                content
+                                    .spawn((
+                                        Node {
+                                            width: Val::Percent(90.),
+                                            height: Val::Px(150.),
+                                            justify_content: JustifyContent::SpaceBetween,
+                                            align_items: AlignItems::Center,
+                                            border: UiRect::all(Val::Px(2.0)),
+                                            ..default()
+                                        },
+                                        BorderColor::all(Color::NONE),
+                                        ButtonInfo {
+                                            border_hover: BORDER_HOVER,
+                                            border_normal: BorderColor::all(Color::NONE),
+                                        },
+                                        BORDER_RADIUS_SQUARE,
+                                        children![
+                                            (
+                                                Text::new(kind.label()),
+                                                TextFont {
+                                                    font: font.clone(),
+                                                    font_size: 52.0,
+                                                    ..default()
+                                                },
+                                                TextColor(Color::WHITE),
+                                            ),
+                                            (
+                                                Text::new(upgrade_status(
+                                                    &upgrades,
+                                                    kind,
+                                                    &gameconfig.shop
+                                                )),
+                                                TextFont {
+                                                    font: font.clone(),
+                                                    font_size: 52.0,
+                                                    ..default()
+                                                },
+                                                TextColor(Color::srgb(0.0, 1.0, 0.0)),
+                                                UpgradeText(kind)
+                                            )
+                                        ],
+                                    ))
+                                    .observe(
+                                        move |_: On<Pointer<Click>>,
+                                              gameconfig: Res<GameConfig>,
+                                              mut upgrades: ResMut<Upgrades>,
+                                              mut credits: Query<
+                                            &mut Text,
+                                            (With<CreditsText>, Without<UpgradeText>),
+                                        >,
+                                              mut texts: Query<
+                                            (&mut Text, &UpgradeText),
+                                            Without<CreditsText>,
+                                        >| {
+                                            if !upgrades.buy(kind, &gameconfig.shop) {
+                                                return;
+                                            }
+                                            save_upgrades(&gameconfig, &upgrades);
+
+                                            for mut text in &mut credits {
+                                                *text =
+                                                    Text::new(format!("{} $", upgrades.credits));
+                                            }
+                                            for (mut text, upgrade) in &mut texts {
+                                                *text = Text::new(upgrade_status(
+                                                    &upgrades,
+                                                    upgrade.0,
+                                                    &gameconfig.shop,
+                                                ));
+                                            }
+                                        },
+                                    );
+                            }
+                        });
+                });
+
+            parent
+                .spawn((
+                    default_node(),
+                    BORDER_NORMAL,
+                    BackgroundColor(Color::srgba(0.0, 0.2, 0.4, 0.8)),
+                    children![(
+                        Text::new("BACK"),
+                        TextFont {
+                            font: font.clone(),
+                            font_size: 32.0,
+                            ..default()
+                        },
+                        TextColor(Color::srgb(0.0, 1.0, 1.0)),
+                    )],
+                ))
+                .observe(
+                    |over: On<Pointer<Over>>, mut colors: Query<&mut BorderColor>| {
+                        *(colors.get_mut(over.entity).unwrap()) = BORDER_HOVER;
+                    },
+                )
+                .observe(
+                    |out: On<Pointer<Out>>, mut colors: Query<&mut BorderColor>| {
+                        *(colors.get_mut(out.entity).unwrap()) = BORDER_NORMAL;
+                    },
+                )
+                .observe(
+                    |_: On<Pointer<Click>>, mut next_state: ResMut<NextState<MenuState>>| {
+                        next_state.set(MenuState::Main);
+                    },
+                );
+        });
+}
+
 pub fn create_gameover_menu_scene(
     mut commands: Commands,
     camera_components: Single<(Entity, &mut Camera), With<MenuCameraComponent>>,
     menu_ressources: Res<UIRessources>,
     score: Res<Score>,
+    upgrades: Res<Upgrades>,
+    voyage: Res<Voyage>,
 ) {
     let (cam_entity, mut camera) = camera_components.into_inner();
     camera.is_active = true;
@@ -393,7 +675,33 @@ pub fn create_gameover_menu_scene(
                 },
                 TextColor(Color::srgb(0.0, 1.0, 1.0)),
                 Node {
-                    margin: UiRect::bottom(Val::Px(60.0)),
+                    margin: UiRect::bottom(Val::Px(10.0)),
+                    ..default()
+                },
+            ));
+            parent.spawn((
+                Text::new(format!("Farthest: {:.1} km", voyage.farthest / 1000.0)),
+                TextFont {
+                    font: font.clone(),
+                    font_size: 30.0,
+                    ..default()
+                },
+                TextColor(Color::srgb(0.0, 1.0, 1.0)),
+                Node {
+                    margin: UiRect::bottom(Val::Px(10.0)),
+                    ..default()
+                },
+            ));
+            parent.spawn((
+                Text::new(format!("Banked: {} $", upgrades.credits)),
+                TextFont {
+                    font: font.clone(),
+                    font_size: 30.0,
+                    ..default()
+                },
+                TextColor(Color::srgb(1.0, 0.85, 0.0)),
+                Node {
+                    margin: UiRect::bottom(Val::Px(50.0)),
                     ..default()
                 },
             ));
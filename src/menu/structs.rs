@@ -1,4 +1,5 @@
 use crate::globals_structs::Action;
+use crate::shop::UpgradeKind;
 use bevy::prelude::*;
 
 ////////////////////////////////////////////////////
@@ -13,6 +14,7 @@ pub enum MenuState {
     None,
     Main,
     Options,
+    Shop,
     GameOver,
 }
 
@@ -53,6 +55,16 @@ pub struct MenuCameraComponent;
 #[derive(Component)]
 pub struct VolumeText;
 
+#[derive(Component)]
+pub struct AimAssistText;
+
+#[derive(Component)]
+pub struct CreditsText;
+
+/// Level and price of an upgrade on the shop scene
+#[derive(Component)]
+pub struct UpgradeText(pub UpgradeKind);
+
 #[derive(EntityEvent, Debug)]
 #[entity_event(propagate, auto_propagate)]
 pub struct Scroll {
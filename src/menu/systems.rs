@@ -237,6 +237,9 @@ fn set_bind(binds: &mut Keybinds, action: Action, button: InputButton) {
         Action::Shoot => binds.shoot = button,
         Action::Menu => binds.menu = button,
         Action::Boost => binds.boost = button,
+        Action::Debug => binds.debug = button,
+        Action::CycleWeapon => binds.cycle_weapon = button,
+        Action::Reload => binds.reload = button,
     }
 }
 
@@ -1,24 +1,63 @@
 use bevy::prelude::*;
 use bevy_hanabi::prelude::*;
 
-use crate::{game_states::GameState, physics::Velocity};
+use crate::{
+    floating_origin::{recenter, OriginShifted},
+    game_states::GameState,
+    physics::Velocity,
+    player::{beam::Beam, missiles::Missile},
+};
 
 pub struct ParticlesPlugin;
 
 impl Plugin for ParticlesPlugin {
     fn build(&self, app: &mut App) {
+        app.add_systems(Startup, (setup_missile_trail, setup_beam_sparks));
         app.add_systems(PostStartup, spawn_particles);
+        app.add_systems(Update, (attach_missile_trails, detach_missile_trails));
         app.add_systems(
             Update,
             enable_disable_rockets_particules.run_if(in_state(GameState::Game)),
         );
+        app.add_systems(Update, show_beam_sparks);
+        // same frame as the recentring, before the effects are extracted for rendering
+        app.add_systems(PostUpdate, shift_particles.after(recenter));
     }
 }
 
+/// Exhaust effect shared by all the missiles
+#[derive(Resource)]
+pub struct MissileTrail(Handle<EffectAsset>);
+
+/// Effect following a missile, left alone by the thruster toggling
+#[derive(Component)]
+pub struct MissileExhaust;
+
+/// Sparks where the beam ray of the same index hits, left alone by the thruster toggling
+#[derive(Component)]
+pub struct BeamSparks(usize);
+
 fn lerp(a: Vec4, b: Vec4, t: f32) -> Vec4 {
     a + (b - a) * t
 }
 
+const SHIFT: &str = "shift";
+
+/// Moves the particles of a global space effect with a recentred world, through a
+/// property that is non zero for a single frame when it happens
+fn with_origin_shift(writer: &ExprWriter) -> SetAttributeModifier {
+    let shift = writer.add_property(SHIFT, Vec3::ZERO.into());
+    let shifted = (writer.attr(Attribute::POSITION) + writer.prop(shift)).expr();
+    SetAttributeModifier::new(Attribute::POSITION, shifted)
+}
+
+/// Properties of an effect built `with_origin_shift`, at rest
+fn shift_properties() -> EffectProperties {
+    let mut props = EffectProperties::default();
+    props.set(SHIFT, Value::Vector(VectorValue::new_vec3(Vec3::ZERO)));
+    props
+}
+
 fn create_rocket_effect(v3color_start: Vec3, v3color_end: Vec3) -> EffectAsset {
     let color_start = Vec4::new(v3color_start.x, v3color_start.y, v3color_start.z, 1.0);
     let color_end = Vec4::new(v3color_end.x, v3color_end.y, v3color_end.z, 1.0);
@@ -54,6 +93,8 @@ fn create_rocket_effect(v3color_start: Vec3, v3color_end: Vec3) -> EffectAsset {
     let drag = writer.lit(4.).expr();
     let update_drag = LinearDragModifier::new(drag);
 
+    let update_shift = with_origin_shift(&writer);
+
     let spawner = SpawnerSettings::rate((100., 300.).into());
 
     let mut size_gradient = bevy_hanabi::Gradient::new();
@@ -76,6 +117,59 @@ fn create_rocket_effect(v3color_start: Vec3, v3color_end: Vec3) -> EffectAsset {
         .init(init_trails_color)
         .with_simulation_space(SimulationSpace::Global)
         .update(update_drag)
+        .update(update_shift)
+        .render(OrientModifier {
+            mode: OrientMode::FaceCameraPosition,
+            ..Default::default()
+        })
+        .render(ColorOverLifetimeModifier {
+            gradient: color_gradient,
+            blend: ColorBlendMode::Overwrite,
+            mask: ColorBlendMask::RGBA,
+        })
+        .render(SizeOverLifetimeModifier {
+            gradient: size_gradient,
+            screen_space_size: false,
+        })
+}
+
+fn create_missile_trail(v3color: Vec3) -> EffectAsset {
+    let color = Vec4::new(v3color.x, v3color.y, v3color.z, 1.0);
+
+    let writer = ExprWriter::new();
+
+    let init_pos = SetPositionCircleModifier {
+        center: writer.lit(Vec3::ZERO).expr(),
+        axis: writer.lit(Vec3::Z).expr(),
+        radius: writer.lit(0.05).expr(),
+        dimension: ShapeDimension::Volume,
+    };
+
+    let age = writer.lit(0.).expr();
+    let init_age = SetAttributeModifier::new(Attribute::AGE, age);
+
+    let lifetime = writer.lit(0.3).uniform(writer.lit(0.6)).expr();
+    let init_lifetime = SetAttributeModifier::new(Attribute::LIFETIME, lifetime);
+
+    let update_shift = with_origin_shift(&writer);
+
+    let spawner = SpawnerSettings::rate(120.0.into());
+
+    let mut size_gradient = bevy_hanabi::Gradient::new();
+    size_gradient.add_key(0.0, Vec3::splat(0.25));
+    size_gradient.add_key(1.0, Vec3::splat(0.02));
+
+    let mut color_gradient = bevy_hanabi::Gradient::new();
+    color_gradient.add_key(0.0, color);
+    color_gradient.add_key(1.0, Vec4::new(0.3, 0.3, 0.3, 0.0));
+
+    EffectAsset::new(256, spawner, writer.finish())
+        .with_name("missile_trail")
+        .init(init_pos)
+        .init(init_age)
+        .init(init_lifetime)
+        .with_simulation_space(SimulationSpace::Global)
+        .update(update_shift)
         .render(OrientModifier {
             mode: OrientMode::FaceCameraPosition,
             ..Default::default()
@@ -91,12 +185,170 @@ fn create_rocket_effect(v3color_start: Vec3, v3color_end: Vec3) -> EffectAsset {
         })
 }
 
+fn create_beam_sparks(v3color: Vec3) -> EffectAsset {
+    let color = Vec4::new(v3color.x, v3color.y, v3color.z, 1.0);
+
+    let writer = ExprWriter::new();
+
+    let init_pos = SetPositionSphereModifier {
+        center: writer.lit(Vec3::ZERO).expr(),
+        radius: writer.lit(0.2).expr(),
+        dimension: ShapeDimension::Volume,
+    };
+
+    let init_vel = SetVelocitySphereModifier {
+        center: writer.lit(Vec3::ZERO).expr(),
+        speed: writer.lit(4.).uniform(writer.lit(10.)).expr(),
+    };
+
+    let age = writer.lit(0.).expr();
+    let init_age = SetAttributeModifier::new(Attribute::AGE, age);
+
+    let lifetime = writer.lit(0.2).uniform(writer.lit(0.4)).expr();
+    let init_lifetime = SetAttributeModifier::new(Attribute::LIFETIME, lifetime);
+
+    let drag = writer.lit(3.).expr();
+    let update_drag = LinearDragModifier::new(drag);
+
+    let update_shift = with_origin_shift(&writer);
+
+    let spawner = SpawnerSettings::rate(200.0.into());
+
+    let mut size_gradient = bevy_hanabi::Gradient::new();
+    size_gradient.add_key(0.0, Vec3::splat(0.2));
+    size_gradient.add_key(1.0, Vec3::splat(0.02));
+
+    let mut color_gradient = bevy_hanabi::Gradient::new();
+    color_gradient.add_key(0.0, Vec4::ONE);
+    color_gradient.add_key(0.3, color);
+    color_gradient.add_key(1.0, Vec4::new(color.x, color.y, color.z, 0.0));
+
+    EffectAsset::new(512, spawner, writer.finish())
+        .with_name("beam_sparks")
+        .init(init_pos)
+        .init(init_vel)
+        .init(init_age)
+        .init(init_lifetime)
+        .with_simulation_space(SimulationSpace::Global)
+        .update(update_drag)
+        .update(update_shift)
+        .render(OrientModifier {
+            mode: OrientMode::FaceCameraPosition,
+            ..Default::default()
+        })
+        .render(ColorOverLifetimeModifier {
+            gradient: color_gradient,
+            blend: ColorBlendMode::Overwrite,
+            mask: ColorBlendMask::RGBA,
+        })
+        .render(SizeOverLifetimeModifier {
+            gradient: size_gradient,
+            screen_space_size: false,
+        })
+}
+
+fn setup_beam_sparks(
+    mut commands: Commands,
+    mut effects: ResMut<Assets<EffectAsset>>,
+    gameconfig: Res<crate::config::structs::GameConfig>,
+) {
+    // sparks in the colour of the first beam weapon
+    let color = gameconfig
+        .ship
+        .weapons
+        .iter()
+        .find(|weapon| weapon.projectile == "beam")
+        .map_or(Vec3::ONE, |weapon| weapon.color);
+    let effect = effects.add(create_beam_sparks(color));
+
+    let props = shift_properties();
+    // one per gun, as the beam segments
+    for index in 0..2 {
+        commands.spawn((
+            Name::new("beam_sparks"),
+            BeamSparks(index),
+            Transform::default(),
+            ParticleEffect::new(effect.clone()),
+            props.clone(),
+        ));
+    }
+}
+
+/// Moves the sparks to the asteroids the beam is cutting
+fn show_beam_sparks(
+    beam: Res<Beam>,
+    mut sparks: Query<(&BeamSparks, &mut Transform, &mut EffectSpawner)>,
+) {
+    for (sparks, mut transform, mut spawner) in &mut sparks {
+        let hit = beam
+            .rays
+            .get(sparks.0)
+            .filter(|ray| ray.hit.is_some())
+            .map(|ray| ray.end);
+        if let Some(position) = hit {
+            transform.translation = position;
+        }
+        spawner.active = hit.is_some();
+    }
+}
+
+fn setup_missile_trail(
+    mut commands: Commands,
+    mut effects: ResMut<Assets<EffectAsset>>,
+    gameconfig: Res<crate::config::structs::GameConfig>,
+) {
+    let effect = effects.add(create_missile_trail(gameconfig.ship.missiles.trail_color));
+    commands.insert_resource(MissileTrail(effect));
+}
+
+fn attach_missile_trails(
+    mut commands: Commands,
+    trail: Res<MissileTrail>,
+    missiles: Query<Entity, Added<Missile>>,
+) {
+    for missile in &missiles {
+        commands.entity(missile).with_child((
+            Name::new("missile_trail"),
+            MissileExhaust,
+            // behind the missile, which faces +Z
+            Transform::from_xyz(0.0, 0.0, -0.3),
+            ParticleEffect::new(trail.0.clone()),
+            shift_properties(),
+        ));
+    }
+}
+
+/// Pooled projectiles outlive their missile, the trail goes with the component
+fn detach_missile_trails(
+    mut commands: Commands,
+    mut removed: RemovedComponents<Missile>,
+    trails: Query<(Entity, &ChildOf), With<MissileExhaust>>,
+) {
+    let removed: Vec<Entity> = removed.read().collect();
+    if removed.is_empty() {
+        return;
+    }
+
+    for (trail, child_of) in &trails {
+        if removed.contains(&child_of.parent()) {
+            commands.entity(trail).despawn();
+        }
+    }
+}
+
 pub fn enable_disable_rockets_particules(
     keyboard: Res<ButtonInput<KeyCode>>,
     mouse: Res<ButtonInput<MouseButton>>,
     keybinds: Res<crate::globals_structs::Keybinds>,
     mut query: ParamSet<(
-        Query<(&mut EffectSpawner, &mut EffectProperties, &GlobalTransform), With<ParticleEffect>>,
+        Query<
+            (&mut EffectSpawner, &mut EffectProperties, &GlobalTransform),
+            (
+                With<ParticleEffect>,
+                Without<MissileExhaust>,
+                Without<BeamSparks>,
+            ),
+        >,
         Single<&Velocity, With<crate::controller::structs::Player>>,
     )>,
 ) {
@@ -126,6 +378,23 @@ pub fn enable_disable_rockets_particules(
     }
 }
 
+/// Global space particles live on the GPU, they are moved with the world for one frame
+pub fn shift_particles(
+    mut shifted: MessageReader<OriginShifted>,
+    mut query: Query<&mut EffectProperties, With<ParticleEffect>>,
+    mut was_shifted: Local<bool>,
+) {
+    let shift: Vec3 = shifted.read().map(|shift| -shift.offset).sum();
+    if shift == Vec3::ZERO && !*was_shifted {
+        return;
+    }
+    *was_shifted = shift != Vec3::ZERO;
+
+    for mut properties in &mut query {
+        properties.set(SHIFT, Value::Vector(VectorValue::new_vec3(shift)));
+    }
+}
+
 pub fn spawn_particles(
     mut commands: Commands,
     mut effects: ResMut<Assets<EffectAsset>>,
@@ -135,7 +404,7 @@ pub fn spawn_particles(
     let position1 = gameconfig.ship.thruster_left;
     let position2 = gameconfig.ship.thruster_right;
 
-    let mut props: EffectProperties = EffectProperties::default();
+    let mut props = shift_properties();
     props.set("direction", Value::Vector(VectorValue::new_vec3(Vec3::Y)));
     props.set("speed", Value::Scalar(ScalarValue::Float(10.0)));
 
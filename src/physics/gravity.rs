@@ -0,0 +1,86 @@
+use bevy::prelude::*;
+
+use crate::config::structs::{GameConfig, GravityConfig};
+use crate::controller::structs::Player;
+use crate::physics::{Parked, Velocity};
+
+/// Attracts every body with a `Velocity`
+#[derive(Component)]
+pub struct GravitySource {
+    pub mass: f32,
+    // pull is capped to its value at this distance from the center
+    pub radius: f32,
+}
+
+/// Acceleration at `position` caused by every source
+pub fn acceleration_at(
+    position: Vec3,
+    config: &GravityConfig,
+    sources: impl IntoIterator<Item = (Vec3, f32, f32)>,
+) -> Vec3 {
+    let mut acceleration = Vec3::ZERO;
+    for (source, mass, radius) in sources {
+        let offset = source - position;
+        let distance = offset.length().max(radius).max(f32::EPSILON);
+        acceleration += offset.normalize_or_zero() * config.strength * mass / (distance * distance);
+    }
+
+    acceleration.clamp_length_max(config.max_acceleration)
+}
+
+pub fn apply_gravity(
+    time: Res<Time>,
+    gameconfig: Res<GameConfig>,
+    sources: Query<(&Transform, &GravitySource)>,
+    mut bodies: Query<(&Transform, &mut Velocity), (Without<GravitySource>, Without<Parked>)>,
+) {
+    let config = &gameconfig.gravity;
+    if !config.enabled {
+        return;
+    }
+
+    let sources: Vec<(Vec3, f32, f32)> = sources
+        .iter()
+        .map(|(tr, source)| (tr.translation, source.mass, source.radius))
+        .collect();
+
+    for (transform, mut velocity) in &mut bodies {
+        velocity.0 += acceleration_at(transform.translation, config, sources.iter().copied())
+            * time.delta_secs();
+    }
+}
+
+/// Predicted path of the ship if it stops thrusting
+pub fn draw_trajectory(
+    mut gizmos: Gizmos,
+    gameconfig: Res<GameConfig>,
+    sources: Query<(&Transform, &GravitySource)>,
+    player: Single<(&Transform, &Velocity), With<Player>>,
+) {
+    let config = &gameconfig.gravity;
+    if !config.enabled || config.trajectory_steps == 0 {
+        return;
+    }
+
+    let sources: Vec<(Vec3, f32, f32)> = sources
+        .iter()
+        .map(|(tr, source)| (tr.translation, source.mass, source.radius))
+        .collect();
+
+    let (transform, velocity) = *player;
+    let mut position = transform.translation;
+    let mut velocity = velocity.0;
+    let mut points = Vec::with_capacity(config.trajectory_steps + 1);
+    points.push((position, Color::srgba(0.3, 0.8, 1.0, 0.8)));
+
+    for step in 1..=config.trajectory_steps {
+        velocity +=
+            acceleration_at(position, config, sources.iter().copied()) * config.trajectory_step;
+        position += velocity * config.trajectory_step;
+
+        let fade = 1.0 - step as f32 / config.trajectory_steps as f32;
+        points.push((position, Color::srgba(0.3, 0.8, 1.0, 0.8 * fade)));
+    }
+
+    gizmos.linestrip_gradient(points);
+}
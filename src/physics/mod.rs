@@ -1,25 +1,198 @@
 use bevy::prelude::*;
 
+use crate::config::structs::{GameConfig, MaterialConfig};
+use crate::game_over::GameOverState;
 use crate::game_states::GameState;
 
+pub mod gravity;
+
 #[derive(Component, Deref, DerefMut)]
+#[require(PhysicsState)]
 pub struct Velocity(pub Vec3);
 
+/// Angular velocity in world space, the axis of rotation scaled by its speed in rad/s
 #[derive(Component, Deref, DerefMut)]
+#[require(PhysicsState)]
 pub struct RotationVelocity(pub Vec3);
 
+/// Body kept out of the simulation and the overlays, like a projectile waiting in its pool
+#[derive(Component)]
+pub struct Parked;
+
+/// Collision response of a body, the ones without it use the manifest default
+#[derive(Component, Clone, Copy, Debug)]
+pub struct PhysicsMaterial {
+    pub restitution: f32,
+    pub friction: f32,
+}
+
+impl PhysicsMaterial {
+    /// Material of a contact: the softer body absorbs the bounce, friction is the geometric mean
+    pub fn combine(&self, other: &PhysicsMaterial) -> PhysicsMaterial {
+        PhysicsMaterial {
+            restitution: self.restitution.min(other.restitution),
+            friction: (self.friction * other.friction).sqrt(),
+        }
+    }
+}
+
+impl From<&MaterialConfig> for PhysicsMaterial {
+    fn from(config: &MaterialConfig) -> Self {
+        PhysicsMaterial {
+            restitution: config.restitution,
+            friction: config.friction,
+        }
+    }
+}
+
+/// Moment of inertia of a solid sphere
+pub fn sphere_inertia(mass: f32, radius: f32) -> f32 {
+    0.4 * mass * radius * radius
+}
+
+/// Ordering of the `FixedUpdate` physics step, collisions always see integrated positions
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub enum PhysicsSystems {
+    Integrate,
+    Collide,
+}
+
+/// Pose of a body at the last two physics ticks, `Transform` shows a blend of both
+#[derive(Component, Default)]
+pub struct PhysicsState {
+    previous: (Vec3, Quat),
+    current: (Vec3, Quat),
+    // pose written by the interpolation, anything else was moved outside of the physics
+    rendered: Option<(Vec3, Quat)>,
+}
+
+impl PhysicsState {
+    /// Position before the current physics tick
+    pub fn previous_translation(&self) -> Vec3 {
+        self.previous.0
+    }
+
+    /// Moves every stored pose along with a recentred world
+    pub fn shift(&mut self, offset: Vec3) {
+        self.previous.0 -= offset;
+        self.current.0 -= offset;
+        if let Some(rendered) = &mut self.rendered {
+            rendered.0 -= offset;
+        }
+    }
+}
+
+/// Earliest fraction of the move from `start` to `end` at which a point gets
+/// within `radius` of the origin
+pub fn sweep_sphere(start: Vec3, end: Vec3, radius: f32) -> Option<f32> {
+    let c = start.length_squared() - radius * radius;
+    if c <= 0.0 {
+        return Some(0.0); // already touching
+    }
+
+    let d = end - start;
+    let a = d.length_squared();
+    let b = start.dot(d);
+    let discriminant = b * b - a * c;
+    if a <= f32::EPSILON || b >= 0.0 || discriminant < 0.0 {
+        return None; // not moving, moving away, or missing
+    }
+
+    let t = (-b - discriminant.sqrt()) / a;
+    (t <= 1.0).then_some(t)
+}
+
 pub struct PhysicsPlugin;
 
 impl Plugin for PhysicsPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(
+        app.configure_sets(
+            FixedUpdate,
+            (PhysicsSystems::Integrate, PhysicsSystems::Collide)
+                .chain()
+                .run_if(in_state(GameState::Game)),
+        )
+        .add_systems(Startup, setup_tick_rate)
+        .add_systems(
+            FixedUpdate,
+            ((gravity::apply_gravity, move_body).chain(), rotate_body)
+                .in_set(PhysicsSystems::Integrate),
+        )
+        .add_systems(
+            RunFixedMainLoop,
+            restore_physics_state.in_set(RunFixedMainLoopSystems::BeforeFixedMainLoop),
+        )
+        .add_systems(FixedFirst, save_previous_state)
+        .add_systems(
+            RunFixedMainLoop,
+            interpolate_transforms.in_set(RunFixedMainLoopSystems::AfterFixedMainLoop),
+        )
+        .add_systems(
             Update,
-            (move_body, rotate_body).run_if(in_state(GameState::Game)),
+            gravity::draw_trajectory
+                .run_if(in_state(GameState::Game))
+                .run_if(in_state(GameOverState::None)),
         );
     }
 }
 
-fn move_body(time: Res<Time>, mut query: Query<(&mut Transform, &Velocity)>) {
+fn setup_tick_rate(mut time: ResMut<Time<Fixed>>, gameconfig: Res<GameConfig>) {
+    time.set_timestep_hz(gameconfig.physics.tick_rate as f64);
+}
+
+/// Puts back the real physics pose before ticking. Moves made outside of the
+/// physics since the last render (steering, resets) are carried over to it
+fn restore_physics_state(mut query: Query<(&mut Transform, &mut PhysicsState)>) {
+    for (mut transform, mut state) in &mut query {
+        let state = &mut *state;
+        let pose = (transform.translation, transform.rotation);
+        match state.rendered {
+            Some(rendered) if rendered != pose => {
+                let translation = pose.0 - rendered.0;
+                let rotation = pose.1 * rendered.1.inverse();
+                for (t, r) in [&mut state.previous, &mut state.current] {
+                    *t += translation;
+                    *r = (rotation * *r).normalize();
+                }
+            }
+            Some(_) => {}
+            None => {
+                state.current = pose;
+                state.previous = pose;
+            }
+        }
+        (transform.translation, transform.rotation) = state.current;
+    }
+}
+
+fn save_previous_state(mut query: Query<(&Transform, &mut PhysicsState)>) {
+    for (transform, mut state) in &mut query {
+        state.previous = (transform.translation, transform.rotation);
+    }
+}
+
+fn interpolate_transforms(
+    time: Res<Time<Fixed>>,
+    gameconfig: Res<GameConfig>,
+    mut query: Query<(&mut Transform, &mut PhysicsState)>,
+) {
+    let alpha = if gameconfig.physics.interpolation {
+        time.overstep_fraction()
+    } else {
+        1.0
+    };
+
+    for (mut transform, mut state) in &mut query {
+        state.current = (transform.translation, transform.rotation);
+
+        let (previous_translation, previous_rotation) = state.previous;
+        transform.translation = previous_translation.lerp(state.current.0, alpha);
+        transform.rotation = previous_rotation.slerp(state.current.1, alpha);
+        state.rendered = Some((transform.translation, transform.rotation));
+    }
+}
+
+fn move_body(time: Res<Time>, mut query: Query<(&mut Transform, &Velocity), Without<Parked>>) {
     for (mut transform, velocity) in &mut query {
         transform.translation += **velocity * time.delta_secs();
     }
@@ -27,13 +200,9 @@ fn move_body(time: Res<Time>, mut query: Query<(&mut Transform, &Velocity)>) {
 
 fn rotate_body(time: Res<Time>, mut query: Query<(&mut Transform, &RotationVelocity)>) {
     for (mut transform, rotation_velocity) in &mut query {
-        let delta_rotation = Quat::from_euler(
-            EulerRot::XYZ,
-            rotation_velocity.0.x * time.delta_secs(),
-            rotation_velocity.0.y * time.delta_secs(),
-            rotation_velocity.0.z * time.delta_secs(),
-        );
+        // world space velocity, so the increment is applied on the left
+        let delta_rotation = Quat::from_scaled_axis(**rotation_velocity * time.delta_secs());
 
-        transform.rotation *= delta_rotation;
+        transform.rotation = (delta_rotation * transform.rotation).normalize();
     }
 }
@@ -0,0 +1,57 @@
+use bevy::audio::Volume;
+
+use crate::config::structs::GameConfig;
+use crate::controller::structs::Player;
+use crate::globals_structs::{MusicVolume, Score};
+use crate::pickups::*;
+use crate::player::{weapons::Arsenal, PlayerHitBox};
+use crate::powerups::{self, PowerUps};
+
+pub fn collect_pickups(
+    mut commands: Commands,
+    gameconfig: Res<GameConfig>,
+    assets: Res<PickupAssets>,
+    master_volume: Res<MusicVolume>,
+    mut inventory: ResMut<PickupInventory>,
+    mut score: ResMut<Score>,
+    mut arsenal: ResMut<Arsenal>,
+    mut powerups: ResMut<PowerUps>,
+    player: Single<&Transform, With<Player>>,
+    player_hitboxes: Query<(&Transform, &PlayerHitBox)>,
+    pickups: Query<(Entity, &Pickup, &Transform)>,
+) {
+    for (pickup_entity, pickup, pickup_transform) in &pickups {
+        let collected = player_hitboxes.iter().any(|(hb_transform, player_hitbox)| {
+            let world_pos =
+                player_hitbox.closest_point(&player, hb_transform, pickup_transform.translation);
+            world_pos.distance(pickup_transform.translation)
+                <= player_hitbox.radius + gameconfig.pickups.radius
+        });
+        if !collected {
+            continue;
+        }
+
+        inventory.add(pickup.kind);
+        match pickup.kind {
+            PickupKind::Ore => score.add(
+                gameconfig.pickups.ore_value as u32,
+                powerups.score_multiplier(),
+            ),
+            PickupKind::Ammo => arsenal.refill(gameconfig.pickups.ammo_refill),
+            PickupKind::PowerUp(kind) => {
+                powerups.activate(kind, &powerups::config(&gameconfig, kind))
+            }
+            _ => {}
+        }
+
+        commands.entity(pickup_entity).despawn();
+        commands.spawn((
+            AudioPlayer::new(assets.sound.clone()),
+            PlaybackSettings {
+                mode: bevy::audio::PlaybackMode::Despawn,
+                volume: Volume::Linear(master_volume.volume / 100.0_f32),
+                ..Default::default()
+            },
+        ));
+    }
+}
@@ -0,0 +1,141 @@
+use bevy::platform::collections::HashMap;
+use bevy::prelude::*;
+
+use crate::config::structs::GameConfig;
+use crate::game_states::GameState;
+use crate::physics::PhysicsSystems;
+use crate::powerups::PowerUpKind;
+
+pub mod collect;
+pub mod spawn;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum PickupKind {
+    Ore,
+    Fuel,
+    Shield,
+    Ammo,
+    PowerUp(PowerUpKind),
+}
+
+impl PickupKind {
+    pub const ALL: [PickupKind; 8] = [
+        PickupKind::Ore,
+        PickupKind::Fuel,
+        PickupKind::Shield,
+        PickupKind::Ammo,
+        PickupKind::PowerUp(PowerUpKind::Shield),
+        PickupKind::PowerUp(PowerUpKind::RapidFire),
+        PickupKind::PowerUp(PowerUpKind::SpreadShot),
+        PickupKind::PowerUp(PowerUpKind::ScoreMultiplier),
+    ];
+
+    /// Name used for the drop tables of the manifest
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "ore" => Some(PickupKind::Ore),
+            "fuel" => Some(PickupKind::Fuel),
+            "shield" => Some(PickupKind::Shield),
+            "ammo" => Some(PickupKind::Ammo),
+            _ => PowerUpKind::parse(name).map(PickupKind::PowerUp),
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            PickupKind::Ore => "ORE",
+            PickupKind::Fuel => "FUEL",
+            PickupKind::Shield => "SHD",
+            PickupKind::Ammo => "AMMO",
+            PickupKind::PowerUp(kind) => kind.label(),
+        }
+    }
+
+    fn color(&self) -> Color {
+        match self {
+            PickupKind::Ore => Color::srgb(1.0, 0.75, 0.2),
+            PickupKind::Fuel => Color::srgb(0.2, 1.0, 0.3),
+            PickupKind::Shield => Color::srgb(0.2, 0.6, 1.0),
+            PickupKind::Ammo => Color::srgb(1.0, 0.2, 0.2),
+            PickupKind::PowerUp(kind) => kind.color(),
+        }
+    }
+}
+
+#[derive(Component)]
+pub struct Pickup {
+    pub kind: PickupKind,
+    lifetime: Timer,
+}
+
+/// What the player collected during the current run
+#[derive(Resource, Default)]
+pub struct PickupInventory {
+    counts: HashMap<PickupKind, u32>,
+}
+
+impl PickupInventory {
+    pub fn add(&mut self, kind: PickupKind) {
+        *self.counts.entry(kind).or_default() += 1;
+    }
+
+    pub fn count(&self, kind: PickupKind) -> u32 {
+        self.counts.get(&kind).copied().unwrap_or(0)
+    }
+
+    pub fn clear(&mut self) {
+        self.counts.clear();
+    }
+}
+
+#[derive(Resource)]
+pub struct PickupAssets {
+    mesh: Handle<Mesh>,
+    materials: HashMap<PickupKind, Handle<StandardMaterial>>,
+    sound: Handle<AudioSource>,
+}
+
+pub struct PickupPlugin;
+
+impl Plugin for PickupPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<PickupInventory>()
+            .add_systems(Startup, setup)
+            .add_systems(
+                FixedUpdate,
+                collect::collect_pickups.in_set(PhysicsSystems::Collide),
+            )
+            .add_systems(
+                Update,
+                (spawn::drop_pickups, spawn::clear_pickups).run_if(in_state(GameState::Game)),
+            );
+    }
+}
+
+fn setup(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    asset_server: Res<AssetServer>,
+    gameconfig: Res<GameConfig>,
+) {
+    let mut pickup_materials: HashMap<PickupKind, Handle<StandardMaterial>> = Default::default();
+    for kind in PickupKind::ALL {
+        pickup_materials.insert(
+            kind,
+            materials.add(StandardMaterial {
+                base_color: kind.color(),
+                emissive: kind.color().to_linear() * 4.0,
+                metallic: 0.8,
+                perceptual_roughness: 0.3,
+                ..default()
+            }),
+        );
+    }
+
+    commands.insert_resource(PickupAssets {
+        mesh: meshes.add(Sphere::new(1.0).mesh().ico(0).unwrap()), // crystal like shape
+        materials: pickup_materials,
+        sound: asset_server.load(gameconfig.pickups.sound.clone()),
+    });
+}
@@ -0,0 +1,162 @@
+use rand::Rng;
+
+use crate::asteroids::messages::AsteroidDestroyed;
+use crate::config::structs::{GameConfig, PickupConfig};
+use crate::controller::structs::Player;
+use crate::physics::{RotationVelocity, Velocity};
+use crate::pickups::*;
+
+/// Rolls the drop table of `size_type` and spawns the resulting pickups,
+/// drifting along with the destroyed asteroid. Ore and fuel are rolled `rolls` times
+pub fn spawn_drops(
+    commands: &mut Commands,
+    assets: &PickupAssets,
+    config: &PickupConfig,
+    size_type: &str,
+    position: Vec3,
+    velocity: Vec3,
+    rolls: usize,
+    rng: &mut impl Rng,
+) {
+    for drop in config.drops.iter().filter(|d| d.size_type == size_type) {
+        let Some(kind) = PickupKind::parse(&drop.kind) else {
+            continue;
+        };
+        let rolls = match kind {
+            PickupKind::Ore | PickupKind::Fuel => rolls,
+            _ => 1,
+        };
+        for _ in 0..rolls {
+            if rng.random::<f32>() >= drop.probability {
+                continue;
+            }
+
+            let scatter = Vec3::new(
+                rng.random_range(-1.0..1.0),
+                rng.random_range(-1.0..1.0),
+                rng.random_range(-1.0..1.0),
+            );
+
+            commands.spawn((
+                Mesh3d(assets.mesh.clone()),
+                MeshMaterial3d(assets.materials.get(&kind).unwrap().clone()),
+                Pickup {
+                    kind,
+                    lifetime: Timer::from_seconds(config.lifetime, TimerMode::Once),
+                },
+                Transform {
+                    translation: position + scatter,
+                    scale: Vec3::splat(config.radius),
+                    rotation: Quat::from_rng(rng),
+                },
+                Velocity(velocity + scatter * 0.5),
+                RotationVelocity(scatter * 2.0),
+            ));
+        }
+    }
+}
+
+pub fn drop_pickups(
+    mut commands: Commands,
+    mut destroyed: MessageReader<AsteroidDestroyed>,
+    gameconfig: Res<GameConfig>,
+    assets: Res<PickupAssets>,
+) {
+    let mut rng = rand::rng();
+
+    // loot is for the player, rocks breaking on their own drop nothing
+    for message in destroyed.read().filter(|message| message.by_player) {
+        spawn_drops(
+            &mut commands,
+            &assets,
+            &gameconfig.pickups,
+            message.size_type,
+            message.position,
+            message.velocity,
+            if message.mined {
+                gameconfig.pickups.mining_rolls
+            } else {
+                1
+            },
+            &mut rng,
+        );
+    }
+}
+
+pub fn clear_pickups(
+    time: Res<Time>,
+    mut commands: Commands,
+    gameconfig: Res<GameConfig>,
+    player: Single<&Transform, With<Player>>,
+    mut query: Query<(Entity, &Transform, &mut Pickup)>,
+) {
+    for (entity, transform, mut pickup) in &mut query {
+        pickup.lifetime.tick(time.delta());
+
+        let distance = transform.translation.distance(player.translation);
+        if pickup.lifetime.is_finished() || distance > gameconfig.asteroids.despawn_range {
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::structs::DropConfig;
+    use crate::tests::destroyed;
+
+    fn drops(app: &mut App, kind: PickupKind) -> usize {
+        let mut query = app.world_mut().query::<&Pickup>();
+        query
+            .iter(app.world())
+            .filter(|pickup| pickup.kind == kind)
+            .count()
+    }
+
+    #[test]
+    fn mined_asteroids_roll_more_ore() {
+        let mut gameconfig = GameConfig::default();
+        gameconfig.pickups.mining_rolls = 3;
+        for kind in ["ore", "shield"] {
+            gameconfig.pickups.drops.push(DropConfig {
+                size_type: "S".to_string(),
+                kind: kind.to_string(),
+                probability: 1.0,
+            });
+        }
+        let mut app = App::new();
+        app.add_message::<AsteroidDestroyed>()
+            .insert_resource(gameconfig)
+            .insert_resource(PickupAssets {
+                mesh: default(),
+                materials: PickupKind::ALL
+                    .into_iter()
+                    .map(|kind| (kind, Handle::default()))
+                    .collect(),
+                sound: default(),
+            })
+            .add_systems(Update, drop_pickups);
+
+        // rocks breaking on their own drop nothing
+        app.world_mut().write_message(AsteroidDestroyed {
+            by_player: false,
+            ..destroyed("S")
+        });
+        app.update();
+        assert_eq!(drops(&mut app, PickupKind::Ore), 0);
+
+        app.world_mut().write_message(destroyed("S"));
+        app.update();
+        assert_eq!(drops(&mut app, PickupKind::Ore), 1);
+
+        app.world_mut().write_message(AsteroidDestroyed {
+            mined: true,
+            ..destroyed("S")
+        });
+        app.update();
+        assert_eq!(drops(&mut app, PickupKind::Ore), 4);
+        // only ore and fuel are rolled again
+        assert_eq!(drops(&mut app, PickupKind::Shield), 2);
+    }
+}
@@ -0,0 +1,212 @@
+use bevy::window::PrimaryWindow;
+
+use crate::asteroids::Asteroid;
+use crate::config::structs::GameConfig;
+use crate::controller::structs::{Player, PlayerCam, VirtualMouse};
+use crate::distancemetric::systems::closest_target;
+use crate::physics::Velocity;
+use crate::player::weapons::{Arsenal, ProjectileKind};
+use crate::player::*;
+
+/// Option of the settings menu, shots are bent toward the lead point
+#[derive(Resource)]
+pub struct AimAssist {
+    pub enabled: bool,
+}
+
+/// Asteroid the HUD leads, and where to shoot to hit it
+#[derive(Resource, Default)]
+pub struct AimTarget {
+    pub target: Option<Entity>,
+    pub lead: Option<Vec3>,
+}
+
+/// HUD ring drawn over the lead point
+#[derive(Component)]
+pub struct LeadMarker;
+
+/// Earliest time at which a shot at `speed` meets a target at `offset` moving
+/// at `velocity`, both relative to the shooter
+pub fn intercept_time(offset: Vec3, velocity: Vec3, speed: f32) -> Option<f32> {
+    let a = velocity.length_squared() - speed * speed;
+    let b = 2.0 * offset.dot(velocity);
+    let c = offset.length_squared();
+
+    if a.abs() < f32::EPSILON {
+        // as fast as the shot, only reachable while closing in
+        let t = -c / b;
+        return (t > 0.0).then_some(t);
+    }
+
+    let discriminant = b * b - 4.0 * a * c;
+    if discriminant < 0.0 {
+        return None;
+    }
+
+    let root = discriminant.sqrt();
+    [(-b - root) / (2.0 * a), (-b + root) / (2.0 * a)]
+        .into_iter()
+        .filter(|t| *t > 0.0)
+        .min_by(|x, y| x.total_cmp(y))
+}
+
+pub fn setup_aim(mut commands: Commands, gameconfig: Res<GameConfig>) {
+    commands.insert_resource(AimAssist {
+        enabled: gameconfig.ship.aim.assist,
+    });
+}
+
+/// Picks the asteroid under the reticle, or the closest one to it within
+/// `target_cone`, and solves its intercept for the current weapon
+pub fn find_lead(
+    gameconfig: Res<GameConfig>,
+    arsenal: Res<Arsenal>,
+    mut aim: ResMut<AimTarget>,
+    camera: Single<(&GlobalTransform, &Camera), With<PlayerCam>>,
+    mouse: Single<&VirtualMouse>,
+    window: Single<&Window, With<PrimaryWindow>>,
+    player: Single<(&Transform, &Velocity), With<Player>>,
+    asteroids: Query<(Entity, &GlobalTransform, &Velocity, &Asteroid), Without<Player>>,
+) {
+    let (cam_transform, camera) = *camera;
+    let (player_transform, player_velocity) = *player;
+
+    let window_dimension = Vec2::new(window.width() / 2., window.height() / 2.);
+    let Ok(ray) = camera.viewport_to_world(cam_transform, mouse.pos + window_dimension) else {
+        *aim = AimTarget::default();
+        return;
+    };
+    let ray_origin = cam_transform.translation();
+    let ray_dir = ray.direction.normalize();
+
+    let range = gameconfig.asteroids.spawn_range;
+    let under_reticle = closest_target(
+        asteroids.iter().map(|(entity, transform, _, asteroid)| {
+            (entity, transform.translation(), asteroid.size)
+        }),
+        ray_origin,
+        ray_dir,
+    )
+    .filter(|(_, distance)| *distance <= range)
+    .map(|(entity, _)| entity);
+
+    let cone = gameconfig.ship.aim.target_cone.to_radians();
+    let target = under_reticle.or_else(|| {
+        asteroids
+            .iter()
+            .filter_map(|(entity, transform, _, _)| {
+                let offset = transform.translation() - ray_origin;
+                let angle = offset.angle_between(ray_dir);
+                (offset.length() <= range && angle <= cone).then_some((entity, angle))
+            })
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(entity, _)| entity)
+    });
+
+    // shots inherit the ship velocity, only the relative motion matters.
+    // A beam hits at once, its lead is the target itself
+    let beam = arsenal.weapon().projectile == ProjectileKind::Beam;
+    let lead = target
+        .and_then(|target| asteroids.get(target).ok())
+        .and_then(|(_, transform, velocity, _)| {
+            if beam {
+                return Some(transform.translation());
+            }
+            let offset = transform.translation() - player_transform.translation;
+            let relative = **velocity - **player_velocity;
+            intercept_time(offset, relative, arsenal.weapon().speed)
+                .map(|t| transform.translation() + relative * t)
+        });
+
+    if aim.target != target || aim.lead != lead {
+        aim.target = target;
+        aim.lead = lead;
+    }
+}
+
+pub fn show_lead(
+    aim: Res<AimTarget>,
+    camera: Single<(&GlobalTransform, &Camera), With<PlayerCam>>,
+    marker: Single<(&mut Node, &mut Visibility, &ComputedNode), With<LeadMarker>>,
+) {
+    let (cam_transform, camera) = *camera;
+    let (mut node, mut visibility, computed) = marker.into_inner();
+
+    let Some(position) = aim
+        .lead
+        .and_then(|lead| camera.world_to_viewport(cam_transform, lead).ok())
+    else {
+        *visibility = Visibility::Hidden;
+        return;
+    };
+
+    let half = computed.size() * computed.inverse_scale_factor() / 2.0;
+    node.left = Val::Px(position.x - half.x);
+    node.top = Val::Px(position.y - half.y);
+    *visibility = Visibility::Inherited;
+}
+
+/// Rotation bending shots from `forward` toward `lead`, if it is within the assist cone
+pub fn assist_rotation(
+    gameconfig: &GameConfig,
+    assist: &AimAssist,
+    aim: &AimTarget,
+    origin: Vec3,
+    forward: Vec3,
+) -> Quat {
+    let Some(wanted) = aim
+        .lead
+        .filter(|_| assist.enabled)
+        .and_then(|lead| (lead - origin).try_normalize())
+    else {
+        return Quat::IDENTITY;
+    };
+
+    if forward.angle_between(wanted) <= gameconfig.ship.aim.assist_cone.to_radians() {
+        Quat::from_rotation_arc(forward, wanted)
+    } else {
+        Quat::IDENTITY
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn intercept_meets_the_target() {
+        let (offset, velocity, speed) = (Vec3::new(0.0, 0.0, -100.0), Vec3::X * 10.0, 50.0);
+        let t = intercept_time(offset, velocity, speed).unwrap();
+        let aim = offset + velocity * t;
+        // the shot covers the distance to the lead point in the same time
+        assert!((aim.length() - speed * t).abs() < 1e-3);
+
+        // fleeing faster than the shot
+        assert!(intercept_time(offset, Vec3::NEG_Z * 60.0, speed).is_none());
+        // static target, straight shot
+        assert!((intercept_time(offset, Vec3::ZERO, speed).unwrap() - 2.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn assist_only_bends_within_its_cone() {
+        let mut gameconfig = GameConfig::default();
+        gameconfig.ship.aim.assist_cone = 5.0;
+        let assist = AimAssist { enabled: true };
+        let aim = |lead: Vec3| AimTarget {
+            target: None,
+            lead: Some(lead),
+        };
+
+        let near = Vec3::new(3.0, 0.0, -100.0);
+        let nudge = assist_rotation(&gameconfig, &assist, &aim(near), Vec3::ZERO, Vec3::NEG_Z);
+        assert!((nudge * Vec3::NEG_Z).angle_between(near) < 1e-3);
+
+        let far = Vec3::new(30.0, 0.0, -100.0);
+        let nudge = assist_rotation(&gameconfig, &assist, &aim(far), Vec3::ZERO, Vec3::NEG_Z);
+        assert_eq!(nudge, Quat::IDENTITY);
+
+        let off = AimAssist { enabled: false };
+        let nudge = assist_rotation(&gameconfig, &off, &aim(near), Vec3::ZERO, Vec3::NEG_Z);
+        assert_eq!(nudge, Quat::IDENTITY);
+    }
+}
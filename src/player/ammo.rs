@@ -4,6 +4,11 @@ use rand::seq::IndexedRandom;
 use crate::config::structs::GameConfig;
 use crate::globals_structs::{Keybinds, MusicVolume};
 use crate::physics::{RotationVelocity, Velocity};
+use crate::player::aim::{assist_rotation, AimAssist, AimTarget};
+use crate::player::missiles::{LockOn, Missile};
+use crate::player::pool::{Projectiles, Shot};
+use crate::player::weapons::{fan, Arsenal, GunHeat, ProjectileKind};
+use crate::powerups::PowerUps;
 use crate::{controller::structs::Player, player::*};
 
 pub fn shoot_ammo(
@@ -12,87 +17,154 @@ pub fn shoot_ammo(
     keybinds: Res<Keybinds>,
     keyboard: Res<ButtonInput<KeyCode>>,
     mouse: Res<ButtonInput<MouseButton>>,
-    mut shoot_side: ResMut<ShootSide>,
+    mut arsenal: ResMut<Arsenal>,
+    mut heat: ResMut<GunHeat>,
+    lock: Res<LockOn>,
+    aim: Res<AimTarget>,
+    assist: Res<AimAssist>,
+    powerups: Res<PowerUps>,
+    mut projectiles: Projectiles,
     player: Single<(&Transform, &Velocity, &RotationVelocity), With<Player>>,
-    assets: Res<AmmoAssets>,
-    audio: Res<ShootSounds>,
     master_volume: Res<MusicVolume>,
 ) {
-    if !keybinds.shoot.just_pressed(&keyboard, &mouse) {
+    if !keybinds.shoot.pressed(&keyboard, &mouse)
+        || arsenal.cooldown > 0.0
+        || arsenal.reload.is_some()
+        || heat.overheated
+    {
         return;
     }
 
-    let (player_tr, player_vel, player_rot) = player.into_inner();
-
-    let local_offset = Vec3::from(if shoot_side.left {
-        game_config.ship.gun_left
-    } else {
-        game_config.ship.gun_right
-    });
-    shoot_side.left = !shoot_side.left;
+    // fired by `beam::fire_beam`
+    if arsenal.weapon().projectile == ProjectileKind::Beam {
+        return;
+    }
 
-    let spawn_pos = player_tr.transform_point(local_offset);
-    let laser_dir = (player_tr.forward().normalize() * 60.0 - local_offset).normalize();
+    let volume = Volume::Linear(master_volume.volume / 100.0_f32);
+    if arsenal.weapon().rounds == 0 {
+        // out of reserve too, one click per trigger pull
+        if keybinds.shoot.just_pressed(&keyboard, &mouse) {
+            commands.spawn((
+                AudioPlayer::new(arsenal.empty_sound.clone()),
+                PlaybackSettings {
+                    mode: bevy::audio::PlaybackMode::Despawn,
+                    volume,
+                    ..Default::default()
+                },
+            ));
+        }
+        return;
+    }
 
-    let world_offset = player_tr.rotation * local_offset;
-    let tangential_vel = player_rot.cross(world_offset);
+    let (player_tr, player_vel, player_rot) = player.into_inner();
 
-    let final_vel = player_vel.0 // inherit ship movement
-              + tangential_vel// inherit rotational motion
-              + laser_dir * game_config.ship.ammo.speed; // base speed
+    let left = arsenal.left;
+    arsenal.left = !arsenal.left;
+    arsenal.cooldown = 1.0 / (arsenal.weapon().fire_rate * powerups.fire_rate_multiplier());
+    arsenal.weapon_mut().rounds -= 1;
+    if arsenal.weapon().rounds == 0 {
+        arsenal.start_reload();
+    }
+    let weapon = arsenal.weapon();
+    let missiles = weapon.projectile == ProjectileKind::Missile;
 
-    let color_vec3: Vec3 = game_config.ship.ammo.color;
-    let color: Color = Color::srgb(color_vec3.x, color_vec3.y, color_vec3.z);
+    if heat.add(weapon.heat) {
+        commands.spawn((
+            AudioPlayer::new(heat.overheat_sound.clone()),
+            PlaybackSettings {
+                mode: bevy::audio::PlaybackMode::Despawn,
+                volume,
+                ..Default::default()
+            },
+        ));
+    }
 
+    // one sound per trigger pull, not per projectile
     let mut rng = rand::rng();
-    if let Some(handle) = audio.shoot_pews.choose(&mut rng) {
+    if let Some(handle) = weapon.sounds.choose(&mut rng) {
         commands.spawn((
-            PointLight {
-                intensity: 100_000.0,
-                range: 20.0,
-                radius: 1.0,
-                color: color,
-                shadows_enabled: false,
-                ..default()
+            AudioPlayer::new(handle.clone()),
+            PlaybackSettings {
+                mode: bevy::audio::PlaybackMode::Despawn,
+                volume,
+                spatial: true,
+                ..Default::default()
             },
-            Transform {
+            Transform::from_translation(player_tr.translation),
+        ));
+    }
+
+    let (extra, angle) = powerups.extra_spread();
+    let muzzles = fan(
+        weapon.muzzles(game_config.ship.gun_left, game_config.ship.gun_right, left),
+        extra,
+        angle,
+    );
+    // the whole volley is bent, spread shots keep their fan
+    let nudge = assist_rotation(
+        &game_config,
+        &assist,
+        &aim,
+        player_tr.translation,
+        player_tr.forward().as_vec3(),
+    );
+    for (local_offset, local_dir) in muzzles {
+        let spawn_pos = player_tr.transform_point(local_offset);
+        let laser_dir = nudge * player_tr.rotation * local_dir;
+
+        let world_offset = player_tr.rotation * local_offset;
+        let tangential_vel = player_rot.cross(world_offset);
+
+        let final_vel = player_vel.0 // inherit ship movement
+              + tangential_vel// inherit rotational motion
+              + laser_dir * weapon.speed; // base speed
+
+        let scale = match weapon.projectile {
+            ProjectileKind::Laser | ProjectileKind::Beam => Vec3::new(0.5, 0.5, 5.0), // ellipse shape
+            ProjectileKind::Plasma => Vec3::splat(weapon.radius),
+            ProjectileKind::Missile => Vec3::ONE,
+        };
+
+        let projectile = projectiles.fire(Shot {
+            transform: Transform {
                 translation: spawn_pos,
-                scale: Vec3::new(0.5, 0.5, 5.0), // ellipse shape
+                scale,
                 rotation: Quat::from_rotation_arc(Vec3::Z, laser_dir),
-                ..Default::default()
             },
-            Ammo,
-            Velocity(final_vel), // fast forward
-            children![
-                (
-                    Mesh3d(assets.mesh.clone()),
-                    MeshMaterial3d(assets.material.clone()),
-                ),
-                (
-                    AudioPlayer::new(handle.clone()),
-                    PlaybackSettings {
-                        mode: bevy::audio::PlaybackMode::Despawn,
-                        volume: Volume::Linear(master_volume.volume / 100.0_f32),
-                        spatial: true,
-                        ..Default::default()
-                    },
-                    Transform::default(),
-                )
-            ],
-        ));
+            velocity: final_vel, // fast forward
+            damage: weapon.damage,
+            radius: weapon.radius,
+            color: weapon.color,
+            mesh: weapon.mesh.clone(),
+            material: weapon.material.clone(),
+        });
+        if missiles {
+            commands.entity(projectile).insert(Missile {
+                target: lock.target,
+            });
+        }
     }
 }
 
 pub fn clear_ammos(
-    mut commands: Commands,
     player: Single<&Transform, With<Player>>,
-    mut query: Query<(Entity, &Transform), With<Ammo>>,
+    mut query: Query<(&Transform, &mut Ammo, Has<Missile>)>,
     game_config: Res<GameConfig>,
 ) {
-    for (entity, transform) in &mut query {
+    for (transform, mut ammo, missile) in &mut query {
+        if !ammo.in_flight {
+            continue;
+        }
+
+        // missiles may chase a lock up to the end of its range
+        let range = if missile {
+            game_config.ship.missiles.lock_range
+        } else {
+            game_config.ship.ammo.distance_despawn
+        };
         let distance = transform.translation.distance(player.translation);
-        if distance > game_config.ship.ammo.distance_despawn {
-            commands.entity(entity).despawn();
+        if distance > range {
+            ammo.in_flight = false;
         }
     }
 }
@@ -0,0 +1,259 @@
+use bevy::audio::Volume;
+
+use crate::asteroids::fragmentation::{fragment, spawn_fragments};
+use crate::asteroids::messages::AsteroidDestroyed;
+use crate::asteroids::{
+    size_type, Asteroid, AsteroidAssets, AsteroidDamage, DespawnAnimation, SpawnAnimation,
+};
+use crate::config::structs::GameConfig;
+use crate::controller::structs::Player;
+use crate::distancemetric::systems::closest_target;
+use crate::globals_structs::{Keybinds, MusicVolume};
+use crate::physics::{RotationVelocity, Velocity};
+use crate::player::aim::{assist_rotation, AimAssist, AimTarget};
+use crate::player::weapons::{Arsenal, GunHeat, ProjectileKind};
+use crate::player::*;
+
+const BEAM_RADIUS: f32 = 0.06;
+
+/// One ray of the beam, `end` is the hit point or the end of the range
+pub struct BeamRay {
+    pub origin: Vec3,
+    pub end: Vec3,
+    pub hit: Option<Entity>,
+}
+
+/// Rays cast this frame, empty while the beam is off
+#[derive(Resource, Default)]
+pub struct Beam {
+    pub rays: Vec<BeamRay>,
+}
+
+/// Mesh drawn along the ray of the same index
+#[derive(Component)]
+pub struct BeamSegment(pub usize);
+
+/// Point where a ray from `origin` along `dir` meets the first of the `asteroids`
+/// (entity, center, radius) within `range`, or the end of the range
+pub fn cast_ray(
+    asteroids: impl IntoIterator<Item = (Entity, Vec3, f32)>,
+    origin: Vec3,
+    dir: Vec3,
+    range: f32,
+) -> (Vec3, Option<Entity>) {
+    match closest_target(asteroids, origin, dir).filter(|(_, distance)| *distance <= range) {
+        Some((entity, distance)) => (origin + dir * distance, Some(entity)),
+        None => (origin + dir * range, None),
+    }
+}
+
+pub fn setup_beam(mut commands: Commands, mut meshes: ResMut<Assets<Mesh>>) {
+    // along Y, stretched to the length of the ray
+    let mesh = meshes.add(Cylinder::new(BEAM_RADIUS, 1.0).mesh());
+    // one per gun
+    for index in 0..2 {
+        commands.spawn((
+            Name::new("beam_segment"),
+            BeamSegment(index),
+            Mesh3d(mesh.clone()),
+            // the one of the current weapon, set when drawn
+            MeshMaterial3d::<StandardMaterial>::default(),
+            Transform::default(),
+            Visibility::Hidden,
+        ));
+    }
+}
+
+/// Casts the rays of a held beam weapon and wears down the first asteroid each one meets.
+/// Asteroids broken this way are mined
+pub fn fire_beam(
+    time: Res<Time>,
+    gameconfig: Res<GameConfig>,
+    mut commands: Commands,
+    keybinds: Res<Keybinds>,
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mouse: Res<ButtonInput<MouseButton>>,
+    assets: Res<AsteroidAssets>,
+    arsenal: Res<Arsenal>,
+    mut heat: ResMut<GunHeat>,
+    mut beam: ResMut<Beam>,
+    aim: Res<AimTarget>,
+    assist: Res<AimAssist>,
+    master_volume: Res<MusicVolume>,
+    mut destroyed_writer: MessageWriter<AsteroidDestroyed>,
+    player: Single<&Transform, With<Player>>,
+    mut asteroids: Query<
+        (
+            Entity,
+            &Asteroid,
+            &Transform,
+            &Velocity,
+            &RotationVelocity,
+            &mut AsteroidDamage,
+        ),
+        (
+            Without<Player>,
+            Without<SpawnAnimation>,
+            Without<DespawnAnimation>,
+        ),
+    >,
+) {
+    beam.rays.clear();
+    let weapon = arsenal.weapon();
+    if weapon.projectile != ProjectileKind::Beam
+        || !keybinds.shoot.pressed(&keyboard, &mouse)
+        || arsenal.reload.is_some()
+        || heat.overheated
+    {
+        return;
+    }
+
+    let dt = time.delta_secs();
+    if heat.add(weapon.heat * dt) {
+        commands.spawn((
+            AudioPlayer::new(heat.overheat_sound.clone()),
+            PlaybackSettings {
+                mode: bevy::audio::PlaybackMode::Despawn,
+                volume: Volume::Linear(master_volume.volume / 100.0_f32),
+                ..Default::default()
+            },
+        ));
+    }
+
+    let nudge = assist_rotation(
+        &gameconfig,
+        &assist,
+        &aim,
+        player.translation,
+        player.forward().as_vec3(),
+    );
+    for (local_offset, local_dir) in
+        weapon.muzzles(gameconfig.ship.gun_left, gameconfig.ship.gun_right, true)
+    {
+        let origin = player.transform_point(local_offset);
+        let dir = nudge * player.rotation * local_dir;
+        let (end, hit) = cast_ray(
+            asteroids
+                .iter()
+                .map(|(entity, asteroid, transform, _, _, _)| {
+                    (entity, transform.translation, asteroid.size)
+                }),
+            origin,
+            dir,
+            weapon.range,
+        );
+        beam.rays.push(BeamRay { origin, end, hit });
+    }
+
+    let mut rng = rand::rng();
+    // both rays may converge on the same rock, it breaks once
+    let mut destroyed: Vec<Entity> = Vec::new();
+    for ray in &beam.rays {
+        if ray.hit.is_some_and(|hit| destroyed.contains(&hit)) {
+            continue;
+        }
+        let Some(Ok((entity, asteroid, transform, velocity, rotation_velocity, mut damage))) =
+            ray.hit.map(|hit| asteroids.get_mut(hit))
+        else {
+            continue;
+        };
+
+        damage.0 += weapon.damage * dt;
+        if damage.0 < asteroid.size * gameconfig.asteroids.hit_points {
+            continue;
+        }
+
+        // crumbles apart, without the push of a projectile
+        destroyed.push(entity);
+        commands.entity(entity).insert(DespawnAnimation::default());
+        destroyed_writer.write(AsteroidDestroyed {
+            asteroid: entity,
+            position: ray.end,
+            velocity: **velocity,
+            impulse: Vec3::ZERO,
+            size: asteroid.size,
+            size_type: size_type(asteroid.size),
+            by_player: true,
+            mined: true,
+        });
+
+        let fragments = fragment(
+            asteroid.size,
+            **velocity,
+            **rotation_velocity,
+            ray.end - transform.translation,
+            Vec3::ZERO,
+            &gameconfig.asteroids.fragmentation,
+            &mut rng,
+        );
+        spawn_fragments(
+            &mut commands,
+            &assets,
+            &gameconfig,
+            transform.translation,
+            &fragments,
+            true,
+            &mut rng,
+        );
+    }
+}
+
+pub fn draw_beam(
+    beam: Res<Beam>,
+    arsenal: Res<Arsenal>,
+    mut segments: Query<(
+        &BeamSegment,
+        &mut Transform,
+        &mut Visibility,
+        &mut MeshMaterial3d<StandardMaterial>,
+    )>,
+) {
+    for (segment, mut transform, mut visibility, mut material) in &mut segments {
+        let Some(ray) = beam.rays.get(segment.0) else {
+            *visibility = Visibility::Hidden;
+            continue;
+        };
+
+        let offset = ray.end - ray.origin;
+        *transform = Transform {
+            translation: ray.origin + offset / 2.0,
+            rotation: Quat::from_rotation_arc(Vec3::Y, offset.normalize_or(Vec3::Y)),
+            scale: Vec3::new(1.0, offset.length(), 1.0),
+        };
+        if material.0 != arsenal.weapon().material {
+            material.0 = arsenal.weapon().material.clone();
+        }
+        *visibility = Visibility::Inherited;
+    }
+}
+
+/// Leaving the game mid-shot must not leave the beam drawn
+pub fn stop_beam(mut beam: ResMut<Beam>, mut segments: Query<&mut Visibility, With<BeamSegment>>) {
+    beam.rays.clear();
+    for mut visibility in &mut segments {
+        *visibility = Visibility::Hidden;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rays_stop_at_the_first_asteroid_in_range() {
+        let near = Entity::from_raw_u32(1).unwrap();
+        let far = Entity::from_raw_u32(2).unwrap();
+        let asteroids = [
+            (far, Vec3::new(0.0, 0.0, -40.0), 2.0),
+            (near, Vec3::new(0.0, 0.0, -20.0), 2.0),
+        ];
+
+        let (end, hit) = cast_ray(asteroids, Vec3::ZERO, Vec3::NEG_Z, 60.0);
+        assert_eq!(hit, Some(near));
+        assert!((end - Vec3::new(0.0, 0.0, -18.0)).length() < 1e-4);
+
+        let (end, hit) = cast_ray(asteroids, Vec3::ZERO, Vec3::NEG_Z, 10.0);
+        assert_eq!(hit, None);
+        assert_eq!(end, Vec3::NEG_Z * 10.0);
+    }
+}
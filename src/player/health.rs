@@ -0,0 +1,174 @@
+use crate::asteroids::messages::{ShipImpact, SunContact};
+use crate::config::structs::{DamageConfig, GameConfig};
+use crate::game_over::GameOverState;
+use crate::player::*;
+use crate::powerups::PowerUps;
+
+/// Hull and shield points of the ship, the shield takes the hits first
+#[derive(Component)]
+pub struct ShipHealth {
+    pub hull: f32,
+    pub shield: f32,
+    // time since the last hit, the shield regenerates after `regen_delay`
+    since_hit: f32,
+}
+
+impl ShipHealth {
+    pub fn new(config: &DamageConfig) -> Self {
+        Self {
+            hull: config.hull,
+            shield: config.shield,
+            since_hit: 0.0,
+        }
+    }
+
+    /// Returns the damage that went through the shield
+    pub fn damage(&mut self, amount: f32) -> f32 {
+        self.since_hit = 0.0;
+        let absorbed = amount.min(self.shield);
+        self.shield -= absorbed;
+        self.hull = (self.hull - (amount - absorbed)).max(0.0);
+        amount - absorbed
+    }
+
+    pub fn is_destroyed(&self) -> bool {
+        self.hull <= 0.0
+    }
+}
+
+/// Damage of a hit, light scrapes under the threshold only bounce the ship
+pub fn impact_damage(impulse: Vec3, config: &DamageConfig) -> f32 {
+    (impulse.length() - config.impulse_threshold).max(0.0) * config.damage_per_impulse
+}
+
+pub fn apply_damage(
+    gameconfig: Res<GameConfig>,
+    mut impacts: MessageReader<ShipImpact>,
+    mut contacts: MessageReader<SunContact>,
+    mut next_state: ResMut<NextState<GameOverState>>,
+    powerups: Res<PowerUps>,
+    mut ships: Query<&mut ShipHealth>,
+) {
+    let config = &gameconfig.ship.damage;
+
+    let hits = impacts
+        .read()
+        .map(|impact| {
+            let damage = impact_damage(impact.impulse, config);
+            debug!(
+                "ship hit by {} asteroid {} at {}, {damage} damage",
+                impact.size_type, impact.asteroid, impact.position
+            );
+            (impact.ship, damage)
+        })
+        .chain(contacts.read().map(|contact| {
+            let damage = impact_damage(contact.impulse, config) * config.sun_multiplier;
+            debug!(
+                "ship burnt by sun {} at {}, {damage} damage",
+                contact.sun, contact.position
+            );
+            (contact.ship, damage)
+        }))
+        .collect::<Vec<_>>();
+
+    for (ship, damage) in hits {
+        let Ok(mut health) = ships.get_mut(ship) else {
+            continue;
+        };
+        if damage <= 0.0 || health.is_destroyed() || powerups.shielded() {
+            continue;
+        }
+
+        health.damage(damage);
+        if health.is_destroyed() {
+            next_state.set(GameOverState::Drift);
+        }
+    }
+}
+
+pub fn regenerate_shield(
+    time: Res<Time>,
+    gameconfig: Res<GameConfig>,
+    mut query: Query<&mut ShipHealth>,
+) {
+    let config = &gameconfig.ship.damage;
+    for mut health in &mut query {
+        health.since_hit += time.delta_secs();
+        if health.since_hit >= config.regen_delay && health.shield < config.shield {
+            health.shield =
+                (health.shield + config.shield_regen * time.delta_secs()).min(config.shield);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::state::app::StatesPlugin;
+
+    use super::*;
+    use crate::tests::impact;
+
+    fn app() -> App {
+        let mut gameconfig = GameConfig::default();
+        gameconfig.ship.damage = DamageConfig {
+            hull: 100.0,
+            shield: 50.0,
+            impulse_threshold: 100.0,
+            damage_per_impulse: 0.1,
+            sun_multiplier: 10.0,
+            ..default()
+        };
+        let mut app = App::new();
+        app.add_plugins(StatesPlugin)
+            .init_state::<GameOverState>()
+            .add_message::<ShipImpact>()
+            .add_message::<SunContact>()
+            .insert_resource(gameconfig)
+            .init_resource::<PowerUps>()
+            .add_systems(Update, apply_damage);
+        app
+    }
+
+    fn spawn_ship(app: &mut App) -> Entity {
+        let health = ShipHealth::new(&app.world().resource::<GameConfig>().ship.damage);
+        app.world_mut().spawn(health).id()
+    }
+
+    #[test]
+    fn impacts_hit_the_shield_first() {
+        let mut app = app();
+        let ship = spawn_ship(&mut app);
+
+        // under the threshold, a scrape
+        app.world_mut().write_message(impact(ship, 90.0));
+        app.update();
+        let health = app.world().get::<ShipHealth>(ship).unwrap();
+        assert_eq!((health.shield, health.hull), (50.0, 100.0));
+
+        app.world_mut().write_message(impact(ship, 800.0));
+        app.update();
+        let health = app.world().get::<ShipHealth>(ship).unwrap();
+        assert_eq!((health.shield, health.hull), (0.0, 80.0));
+    }
+
+    #[test]
+    fn sun_burns_through_the_hull() {
+        let mut app = app();
+        let ship = spawn_ship(&mut app);
+
+        app.world_mut().write_message(SunContact {
+            ship,
+            sun: Entity::PLACEHOLDER,
+            position: Vec3::ZERO,
+            impulse: Vec3::X * 250.0,
+        });
+        app.update();
+        assert!(app.world().get::<ShipHealth>(ship).unwrap().is_destroyed());
+
+        app.update();
+        assert_eq!(
+            *app.world().resource::<State<GameOverState>>().get(),
+            GameOverState::Drift
+        );
+    }
+}
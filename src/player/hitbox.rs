@@ -0,0 +1,161 @@
+use bevy::mesh::VertexAttributeValues;
+use bevy::scene::{SceneInstance, SceneSpawner};
+
+use crate::config::structs::{GameConfig, HitboxConfig};
+use crate::controller::structs::Player;
+use crate::player::*;
+
+/// Ship whose hitboxes are still to be fitted to its model
+#[derive(Component)]
+pub struct HitboxFitPending;
+
+pub fn hitbox(config: &HitboxConfig) -> (PlayerHitBox, Transform) {
+    (
+        PlayerHitBox {
+            radius: config.radius,
+            half_axis: config.half_axis,
+        },
+        Transform::from_translation(config.center),
+    )
+}
+
+/// Hitboxes of the manifest, with a pending fit if one is asked for
+pub fn spawn_hitboxes(commands: &mut Commands, ship: Entity, gameconfig: &GameConfig) {
+    let config = &gameconfig.ship.hitboxes;
+    let mut ship = commands.entity(ship);
+    ship.with_children(|parent| {
+        for shape in &config.shapes {
+            parent.spawn(hitbox(shape));
+        }
+    });
+    if config.auto_fit != "none" {
+        ship.insert(HitboxFitPending);
+    }
+}
+
+// longest side of the box, and the radius covering the other two
+fn long_axis(half: Vec3) -> (Vec3, f32, f32) {
+    if half.x >= half.y && half.x >= half.z {
+        (Vec3::X, half.x, half.y.max(half.z))
+    } else if half.y >= half.z {
+        (Vec3::Y, half.y, half.x.max(half.z))
+    } else {
+        (Vec3::Z, half.z, half.x.max(half.y))
+    }
+}
+
+/// `count` spheres strung along the longest side of the box `min..max`
+pub fn fit_spheres(min: Vec3, max: Vec3, count: usize) -> Vec<HitboxConfig> {
+    let center = (min + max) / 2.0;
+    let (axis, length, radius) = long_axis((max - min) / 2.0);
+    if count <= 1 || length <= radius {
+        return vec![HitboxConfig {
+            center,
+            radius: length.max(radius),
+            half_axis: Vec3::ZERO,
+        }];
+    }
+
+    let reach = length - radius;
+    (0..count)
+        .map(|i| HitboxConfig {
+            center: center + axis * (-reach + 2.0 * reach * i as f32 / (count - 1) as f32),
+            radius,
+            half_axis: Vec3::ZERO,
+        })
+        .collect()
+}
+
+/// One capsule along the longest side of the box `min..max`
+pub fn fit_capsule(min: Vec3, max: Vec3) -> HitboxConfig {
+    let (axis, length, radius) = long_axis((max - min) / 2.0);
+    HitboxConfig {
+        center: (min + max) / 2.0,
+        radius,
+        half_axis: axis * (length - radius).max(0.0),
+    }
+}
+
+/// Replaces the manifest hitboxes once the ship model is in, from the bounds of its meshes
+pub fn fit_hitboxes(
+    mut commands: Commands,
+    gameconfig: Res<GameConfig>,
+    meshes: Res<Assets<Mesh>>,
+    scenes: Res<SceneSpawner>,
+    ship: Single<
+        (Entity, &GlobalTransform, &SceneInstance),
+        (With<Player>, With<HitboxFitPending>),
+    >,
+    parts: Query<(&Mesh3d, &GlobalTransform)>,
+    hitboxes: Query<Entity, With<PlayerHitBox>>,
+) {
+    let (entity, ship_transform, instance) = *ship;
+    // the cockpit screens are meshes of the ship too, only the model counts
+    if !scenes.instance_is_ready(**instance) {
+        return;
+    }
+    let to_ship = ship_transform.affine().inverse();
+
+    let mut bounds: Option<(Vec3, Vec3)> = None;
+    for part in scenes.iter_instance_entities(**instance) {
+        let Ok((mesh, transform)) = parts.get(part) else {
+            continue;
+        };
+        let Some(mesh) = meshes.get(&mesh.0) else {
+            return; // still loading
+        };
+        let Some(VertexAttributeValues::Float32x3(positions)) =
+            mesh.attribute(Mesh::ATTRIBUTE_POSITION)
+        else {
+            continue;
+        };
+
+        let to_local = to_ship * transform.affine();
+        for position in positions {
+            let point = to_local.transform_point3(Vec3::from(*position));
+            bounds = Some(bounds.map_or((point, point), |(min, max)| {
+                (min.min(point), max.max(point))
+            }));
+        }
+    }
+    let Some((min, max)) = bounds else {
+        return;
+    };
+
+    let config = &gameconfig.ship.hitboxes;
+    let shapes = match config.auto_fit.as_str() {
+        "capsule" => vec![fit_capsule(min, max)],
+        _ => fit_spheres(min, max, config.fit_count),
+    };
+
+    for hitbox in &hitboxes {
+        commands.entity(hitbox).despawn();
+    }
+    commands
+        .entity(entity)
+        .remove::<HitboxFitPending>()
+        .with_children(|parent| {
+            for shape in &shapes {
+                parent.spawn(hitbox(shape));
+            }
+        });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fits_cover_the_bounds() {
+        let (min, max) = (Vec3::new(-1.0, -0.5, -4.0), Vec3::new(1.0, 0.5, 4.0));
+
+        let spheres = fit_spheres(min, max, 3);
+        let centers: Vec<f32> = spheres.iter().map(|shape| shape.center.z).collect();
+        assert_eq!(centers, vec![-3.0, 0.0, 3.0]);
+        assert!(spheres.iter().all(|shape| shape.radius == 1.0));
+
+        let capsule = fit_capsule(min, max);
+        assert_eq!(capsule.half_axis, Vec3::Z * 3.0);
+        assert_eq!(capsule.radius, 1.0);
+    }
+}
@@ -0,0 +1,148 @@
+use bevy::window::PrimaryWindow;
+
+use crate::asteroids::Asteroid;
+use crate::config::structs::GameConfig;
+use crate::controller::structs::{PlayerCam, VirtualMouse};
+use crate::distancemetric::systems::closest_target;
+use crate::physics::Velocity;
+use crate::player::*;
+
+/// Guided projectile, steers toward `target` while it exists
+#[derive(Component)]
+pub struct Missile {
+    pub target: Option<Entity>,
+}
+
+/// Asteroid under the reticle being locked, and the last one fully locked
+#[derive(Resource, Default)]
+pub struct LockOn {
+    pub candidate: Option<Entity>,
+    // seconds the candidate stayed under the reticle
+    pub progress: f32,
+    pub target: Option<Entity>,
+}
+
+pub fn track_lock(
+    time: Res<Time>,
+    gameconfig: Res<GameConfig>,
+    mut lock: ResMut<LockOn>,
+    camera: Single<(&GlobalTransform, &Camera), With<PlayerCam>>,
+    mouse: Single<&VirtualMouse>,
+    window: Single<&Window, With<PrimaryWindow>>,
+    asteroids: Query<(Entity, &GlobalTransform, &Asteroid)>,
+) {
+    let config = &gameconfig.ship.missiles;
+    let (cam_transform, camera) = *camera;
+    let ray_origin = cam_transform.translation();
+
+    // a lock is kept until its asteroid is gone or out of range
+    if let Some(target) = lock.target {
+        let in_range = asteroids.get(target).is_ok_and(|(_, transform, _)| {
+            transform.translation().distance(ray_origin) <= config.lock_range
+        });
+        if !in_range {
+            lock.target = None;
+        }
+    }
+
+    let window_dimension = Vec2::new(window.width() / 2., window.height() / 2.);
+    let hovered = camera
+        .viewport_to_world(cam_transform, mouse.pos + window_dimension)
+        .ok()
+        .and_then(|ray| {
+            closest_target(
+                asteroids.iter().map(|(entity, transform, asteroid)| {
+                    (entity, transform.translation(), asteroid.size)
+                }),
+                ray_origin,
+                ray.direction.normalize(),
+            )
+        })
+        .filter(|(_, distance)| *distance <= config.lock_range)
+        .map(|(entity, _)| entity);
+
+    if hovered != lock.candidate {
+        lock.candidate = hovered;
+        lock.progress = 0.0;
+        return;
+    }
+
+    if let Some(candidate) = hovered {
+        lock.progress += time.delta_secs();
+        if lock.progress >= config.lock_time && lock.target != Some(candidate) {
+            lock.target = Some(candidate);
+        }
+    }
+}
+
+/// Ring around the locked asteroid, growing on the one being locked
+pub fn draw_lock(
+    mut gizmos: Gizmos,
+    gameconfig: Res<GameConfig>,
+    lock: Res<LockOn>,
+    camera: Single<&GlobalTransform, With<PlayerCam>>,
+    asteroids: Query<(&GlobalTransform, &Asteroid)>,
+) {
+    let facing = |position: Vec3| {
+        Isometry3d::new(
+            position,
+            Quat::from_rotation_arc(
+                Vec3::Z,
+                (camera.translation() - position).normalize_or_zero(),
+            ),
+        )
+    };
+
+    if let Some((transform, asteroid)) = lock.target.and_then(|t| asteroids.get(t).ok()) {
+        gizmos.circle(
+            facing(transform.translation()),
+            asteroid.size * 1.3,
+            Color::srgb(1.0, 0.2, 0.1),
+        );
+    }
+
+    let locking = lock
+        .candidate
+        .filter(|candidate| lock.target != Some(*candidate));
+    if let Some((transform, asteroid)) = locking.and_then(|t| asteroids.get(t).ok()) {
+        let progress = (lock.progress / gameconfig.ship.missiles.lock_time).min(1.0);
+        gizmos.circle(
+            facing(transform.translation()),
+            asteroid.size * (2.5 - 1.2 * progress),
+            Color::srgba(1.0, 0.8, 0.2, 0.3 + 0.7 * progress),
+        );
+    }
+}
+
+/// Turns the missiles toward their target by at most `turn_rate`, keeping their speed
+pub fn steer_missiles(
+    time: Res<Time>,
+    gameconfig: Res<GameConfig>,
+    mut missiles: Query<(&mut Transform, &mut Velocity, &Missile), Without<Asteroid>>,
+    asteroids: Query<&Transform, With<Asteroid>>,
+) {
+    let max_angle = gameconfig.ship.missiles.turn_rate.to_radians() * time.delta_secs();
+
+    for (mut transform, mut velocity, missile) in &mut missiles {
+        let Some(target) = missile.target.and_then(|t| asteroids.get(t).ok()) else {
+            continue;
+        };
+        let (Some(heading), Some(wanted)) = (
+            velocity.try_normalize(),
+            (target.translation - transform.translation).try_normalize(),
+        ) else {
+            continue;
+        };
+
+        let angle = heading.angle_between(wanted);
+        let new_heading = if angle <= max_angle {
+            wanted
+        } else {
+            let axis = heading.cross(wanted).try_normalize().unwrap_or(Vec3::Y);
+            Quat::from_axis_angle(axis, max_angle) * heading
+        };
+
+        **velocity = new_heading * velocity.length();
+        transform.rotation = Quat::from_rotation_arc(Vec3::Z, new_heading);
+    }
+}
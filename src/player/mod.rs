@@ -1,69 +1,100 @@
 use bevy::prelude::*;
 
-use crate::{config::structs::GameConfig, game_states::GameState};
+use crate::{game_over::GameOverState, game_states::GameState, physics::PhysicsSystems};
+pub mod aim;
 pub mod ammo;
+pub mod beam;
+pub mod health;
+pub mod hitbox;
+pub mod missiles;
+pub mod pool;
+pub mod weapons;
 
+/// Sphere, or capsule when `half_axis` isn't zero. Its `Transform` places it in ship space
 #[derive(Component)]
 pub struct PlayerHitBox {
     pub radius: f32,
+    // half of the capsule segment in ship space
+    pub half_axis: Vec3,
 }
 
-#[derive(Component)]
-pub struct Ammo;
-
-#[derive(Resource)]
-pub struct ShootSide {
-    left: bool,
-}
-
-#[derive(Resource)]
-pub struct AmmoAssets {
-    mesh: Handle<Mesh>,
-    material: Handle<StandardMaterial>,
+impl PlayerHitBox {
+    /// Point of the hitbox core closest to `target`, in world space
+    pub fn closest_point(&self, ship: &Transform, local: &Transform, target: Vec3) -> Vec3 {
+        let center = ship.transform_point(local.translation);
+        let axis = ship.rotation * self.half_axis;
+        if axis == Vec3::ZERO {
+            return center;
+        }
+        let t = (target - center).dot(axis) / axis.length_squared();
+        center + axis * t.clamp(-1.0, 1.0)
+    }
 }
 
-#[derive(Resource, Default)]
-pub struct ShootSounds {
-    pub shoot_pews: Vec<Handle<AudioSource>>,
+/// Projectile, `radius` is used for the swept hits. Clearing `in_flight`
+/// hands it back to the pool
+#[derive(Component)]
+pub struct Ammo {
+    pub damage: f32,
+    pub radius: f32,
+    pub in_flight: bool,
 }
 
 pub const PLAYER_MASS: f32 = 216.0; //6³
+pub const PLAYER_RADIUS: f32 = 1.5; // used for the ship inertia
 
 pub struct PlayerPlugin;
 
 impl Plugin for PlayerPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(Startup, setup).add_systems(
-            Update,
-            (ammo::shoot_ammo, ammo::clear_ammos).run_if(in_state(GameState::Game)),
-        );
+        app.init_resource::<missiles::LockOn>()
+            .init_resource::<aim::AimTarget>()
+            .init_resource::<pool::ProjectilePool>()
+            .init_resource::<beam::Beam>()
+            .add_systems(
+                Startup,
+                (
+                    weapons::setup_arsenal,
+                    pool::prewarm_pool,
+                    aim::setup_aim,
+                    beam::setup_beam,
+                ),
+            )
+            .add_systems(
+                Update,
+                (
+                    weapons::cycle_weapon,
+                    weapons::cool_guns,
+                    weapons::reload_weapon.after(weapons::cycle_weapon),
+                    ammo::shoot_ammo
+                        .after(weapons::reload_weapon)
+                        .after(aim::find_lead)
+                        .after(weapons::cool_guns)
+                        .after(pool::recycle_ammos),
+                    (beam::fire_beam, beam::draw_beam)
+                        .chain()
+                        .after(weapons::reload_weapon)
+                        .after(aim::find_lead)
+                        .after(weapons::cool_guns),
+                    (ammo::clear_ammos, pool::recycle_ammos).chain(),
+                    (missiles::track_lock, missiles::draw_lock).chain(),
+                    (aim::find_lead.after(weapons::cycle_weapon), aim::show_lead).chain(),
+                    hitbox::fit_hitboxes.run_if(any_with_component::<hitbox::HitboxFitPending>),
+                )
+                    .run_if(in_state(GameState::Game)),
+            )
+            .add_systems(OnExit(GameState::Game), beam::stop_beam)
+            .add_systems(
+                FixedUpdate,
+                missiles::steer_missiles
+                    .before(PhysicsSystems::Integrate)
+                    .run_if(in_state(GameState::Game)),
+            )
+            .add_systems(
+                Update,
+                (health::apply_damage, health::regenerate_shield)
+                    .run_if(in_state(GameState::Game))
+                    .run_if(in_state(GameOverState::None)),
+            );
     }
 }
-
-pub fn setup(
-    mut commands: Commands,
-    mut meshes: ResMut<Assets<Mesh>>,
-    mut materials: ResMut<Assets<StandardMaterial>>,
-    asset_server: Res<AssetServer>,
-    gameconfig: Res<GameConfig>,
-) {
-    let material = materials.add(StandardMaterial {
-        base_color: Color::hsl(280.0, 0.8, 0.6), // vivid purple
-        emissive: LinearRgba::rgb(10.0, 0.0, 15.0),
-        ..default()
-    });
-    let mesh = meshes.add(Sphere::new(0.05).mesh());
-
-    commands.insert_resource(AmmoAssets {
-        mesh: mesh,
-        material: material,
-    });
-
-    commands.insert_resource(ShootSide { left: true });
-    let mut resource = ShootSounds::default();
-    for path in gameconfig.ship.ammo.sounds.iter() {
-        resource.shoot_pews.push(asset_server.load(path))
-    }
-
-    commands.insert_resource(resource);
-}
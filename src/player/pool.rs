@@ -0,0 +1,234 @@
+use bevy::ecs::system::SystemParam;
+
+use crate::config::structs::GameConfig;
+use crate::controller::structs::Player;
+use crate::physics::{Parked, Velocity};
+use crate::player::missiles::Missile;
+use crate::player::*;
+
+/// Projectile entities kept around between shots. A spent projectile is
+/// hidden and parked instead of despawned, only its components change
+#[derive(Resource, Default)]
+pub struct ProjectilePool {
+    free: Vec<Entity>,
+    // projectiles in flight with their light on
+    pub lit: usize,
+    // every projectile entity ever spawned by the pool
+    pub size: usize,
+}
+
+/// Children of a pooled projectile, reused along with it
+#[derive(Component)]
+pub struct PooledProjectile {
+    mesh: Entity,
+    light: Entity,
+    lit: bool,
+}
+
+/// Everything that changes from one shot to the next
+pub struct Shot {
+    pub transform: Transform,
+    pub velocity: Vec3,
+    pub damage: f32,
+    pub radius: f32,
+    pub color: Color,
+    pub mesh: Handle<Mesh>,
+    pub material: Handle<StandardMaterial>,
+}
+
+fn projectile_light(color: Color) -> PointLight {
+    PointLight {
+        intensity: 100_000.0,
+        range: 20.0,
+        radius: 1.0,
+        color,
+        shadows_enabled: false,
+        ..default()
+    }
+}
+
+fn light_visibility(lit: bool) -> Visibility {
+    if lit {
+        Visibility::Inherited
+    } else {
+        // emissive only, past the light cap
+        Visibility::Hidden
+    }
+}
+
+fn spawn_projectile(commands: &mut Commands, shot: Option<&Shot>, lit: bool) -> Entity {
+    let mesh = commands
+        .spawn((
+            Mesh3d(shot.map(|shot| shot.mesh.clone()).unwrap_or_default()),
+            MeshMaterial3d(shot.map(|shot| shot.material.clone()).unwrap_or_default()),
+        ))
+        .id();
+    let light = commands
+        .spawn((
+            projectile_light(shot.map_or(Color::WHITE, |shot| shot.color)),
+            light_visibility(lit),
+        ))
+        .id();
+
+    let (transform, velocity, ammo, visibility) = match shot {
+        Some(shot) => (
+            shot.transform,
+            shot.velocity,
+            Ammo {
+                damage: shot.damage,
+                radius: shot.radius,
+                in_flight: true,
+            },
+            Visibility::Inherited,
+        ),
+        None => (
+            Transform::default(),
+            Vec3::ZERO,
+            Ammo {
+                damage: 0.0,
+                radius: 0.0,
+                in_flight: false,
+            },
+            Visibility::Hidden,
+        ),
+    };
+
+    let mut projectile = commands.spawn((
+        transform,
+        visibility,
+        Velocity(velocity),
+        ammo,
+        PooledProjectile { mesh, light, lit },
+    ));
+    if shot.is_none() {
+        projectile.insert(Parked);
+    }
+    projectile.add_children(&[mesh, light]).id()
+}
+
+/// Fills the pool up front so the first bursts do not spawn anything
+pub fn prewarm_pool(
+    mut commands: Commands,
+    gameconfig: Res<GameConfig>,
+    mut pool: ResMut<ProjectilePool>,
+) {
+    for _ in 0..gameconfig.ship.ammo.pool_size {
+        let entity = spawn_projectile(&mut commands, None, false);
+        pool.free.push(entity);
+        pool.size += 1;
+    }
+}
+
+#[derive(SystemParam)]
+pub struct Projectiles<'w, 's> {
+    commands: Commands<'w, 's>,
+    gameconfig: Res<'w, GameConfig>,
+    pool: ResMut<'w, ProjectilePool>,
+    roots: Query<
+        'w,
+        's,
+        (
+            &'static mut Transform,
+            &'static mut Visibility,
+            &'static mut Velocity,
+            &'static mut Ammo,
+            &'static mut PooledProjectile,
+        ),
+        Without<Player>,
+    >,
+    meshes: Query<
+        'w,
+        's,
+        (
+            &'static mut Mesh3d,
+            &'static mut MeshMaterial3d<StandardMaterial>,
+        ),
+    >,
+    lights: Query<'w, 's, (&'static mut PointLight, &'static mut Visibility), Without<Ammo>>,
+}
+
+impl Projectiles<'_, '_> {
+    /// Launches `shot` from a free projectile of the pool, or a new one when it is empty
+    pub fn fire(&mut self, shot: Shot) -> Entity {
+        let lit = self.pool.lit < self.gameconfig.ship.ammo.max_lights;
+        if lit {
+            self.pool.lit += 1;
+        }
+
+        while let Some(entity) = self.pool.free.pop() {
+            // despawned behind the pool's back, by a world reset for instance
+            let Ok((mut transform, mut visibility, mut velocity, mut ammo, mut pooled)) =
+                self.roots.get_mut(entity)
+            else {
+                self.pool.size -= 1;
+                continue;
+            };
+
+            self.commands.entity(entity).remove::<Parked>();
+            *transform = shot.transform;
+            *visibility = Visibility::Inherited;
+            **velocity = shot.velocity;
+            *ammo = Ammo {
+                damage: shot.damage,
+                radius: shot.radius,
+                in_flight: true,
+            };
+            pooled.lit = lit;
+
+            if let Ok((mut mesh, mut material)) = self.meshes.get_mut(pooled.mesh) {
+                mesh.0 = shot.mesh;
+                material.0 = shot.material;
+            }
+            if let Ok((mut light, mut visibility)) = self.lights.get_mut(pooled.light) {
+                light.color = shot.color;
+                *visibility = light_visibility(lit);
+            }
+            return entity;
+        }
+
+        self.pool.size += 1;
+        spawn_projectile(&mut self.commands, Some(&shot), lit)
+    }
+}
+
+/// Hides and parks the projectiles that stopped flying, pooled ones go back to the pool
+pub fn recycle_ammos(
+    mut commands: Commands,
+    mut pool: ResMut<ProjectilePool>,
+    mut query: Query<
+        (
+            Entity,
+            &Ammo,
+            &mut Velocity,
+            Option<(&mut Visibility, &mut PooledProjectile)>,
+            Has<Missile>,
+        ),
+        Changed<Ammo>,
+    >,
+) {
+    for (entity, ammo, mut velocity, pooled, missile) in &mut query {
+        if ammo.in_flight {
+            continue;
+        }
+        let Some((mut visibility, mut pooled)) = pooled else {
+            commands.entity(entity).despawn();
+            continue;
+        };
+        if *visibility == Visibility::Hidden {
+            continue; // already parked
+        }
+
+        *visibility = Visibility::Hidden;
+        **velocity = Vec3::ZERO;
+        if pooled.lit {
+            pooled.lit = false;
+            pool.lit -= 1;
+        }
+        // out of gravity and integration until fired again
+        commands.entity(entity).insert(Parked);
+        if missile {
+            commands.entity(entity).remove::<Missile>();
+        }
+        pool.free.push(entity);
+    }
+}
@@ -0,0 +1,357 @@
+use crate::config::structs::{GameConfig, WeaponConfig};
+use crate::globals_structs::Keybinds;
+use crate::player::*;
+
+/// Shape of the shots, drawn differently and sized by the weapon radius
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ProjectileKind {
+    /// Thin stretched bolt
+    Laser,
+    /// Glowing ball
+    Plasma,
+    /// Guided toward the locked asteroid
+    Missile,
+    /// Continuous ray, no projectile
+    Beam,
+}
+
+impl ProjectileKind {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "laser" => Some(ProjectileKind::Laser),
+            "plasma" => Some(ProjectileKind::Plasma),
+            "missile" => Some(ProjectileKind::Missile),
+            "beam" => Some(ProjectileKind::Beam),
+            _ => None,
+        }
+    }
+}
+
+/// Which guns fire on a trigger pull
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FirePattern {
+    /// One gun after the other
+    Alternate,
+    /// Both guns at once
+    Simultaneous,
+    /// A fan of `spread_count` shots from one gun after the other
+    Spread,
+}
+
+impl FirePattern {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "alternate" => Some(FirePattern::Alternate),
+            "simultaneous" => Some(FirePattern::Simultaneous),
+            "spread" => Some(FirePattern::Spread),
+            _ => None,
+        }
+    }
+}
+
+/// A manifest weapon with its assets loaded
+pub struct Weapon {
+    pub name: String,
+    pub projectile: ProjectileKind,
+    pub pattern: FirePattern,
+    pub speed: f32,
+    pub damage: f32,
+    pub radius: f32,
+    pub color: Color,
+    pub spread_count: usize,
+    // full angle of the fan, in radians
+    pub spread_angle: f32,
+    pub fire_rate: f32,
+    pub heat: f32,
+    pub range: f32,
+    pub magazine: usize,
+    pub max_reserve: usize,
+    pub reload_time: f32,
+    // trigger pulls left in the magazine
+    pub rounds: usize,
+    pub reserve: usize,
+    pub mesh: Handle<Mesh>,
+    pub material: Handle<StandardMaterial>,
+    pub sounds: Vec<Handle<AudioSource>>,
+}
+
+impl Weapon {
+    /// Muzzle offsets (ship space) and directions (ship space) of one trigger pull
+    pub fn muzzles(&self, gun_left: Vec3, gun_right: Vec3, left: bool) -> Vec<(Vec3, Vec3)> {
+        // shots converge 60 units ahead
+        let aim = |offset: Vec3| (Vec3::NEG_Z * 60.0 - offset).normalize();
+        let side = if left { gun_left } else { gun_right };
+
+        match self.pattern {
+            FirePattern::Alternate => vec![(side, aim(side))],
+            FirePattern::Simultaneous => {
+                vec![(gun_left, aim(gun_left)), (gun_right, aim(gun_right))]
+            }
+            FirePattern::Spread => {
+                let count = self.spread_count.max(1);
+                (0..count)
+                    .map(|i| {
+                        let t = if count == 1 {
+                            0.0
+                        } else {
+                            i as f32 / (count - 1) as f32 - 0.5
+                        };
+                        (
+                            side,
+                            Quat::from_rotation_y(t * self.spread_angle) * aim(side),
+                        )
+                    })
+                    .collect()
+            }
+        }
+    }
+}
+
+/// Adds `extra` copies of every shot, fanned over `angle` around it
+pub fn fan(muzzles: Vec<(Vec3, Vec3)>, extra: usize, angle: f32) -> Vec<(Vec3, Vec3)> {
+    if extra == 0 {
+        return muzzles;
+    }
+
+    let count = extra + 1;
+    muzzles
+        .into_iter()
+        .flat_map(|(offset, dir)| {
+            (0..count).map(move |i| {
+                let t = i as f32 / (count - 1) as f32 - 0.5;
+                (offset, Quat::from_rotation_y(t * angle) * dir)
+            })
+        })
+        .collect()
+}
+
+/// Weapons of the ship, in manifest order
+#[derive(Resource)]
+pub struct Arsenal {
+    pub weapons: Vec<Weapon>,
+    pub current: usize,
+    // next gun for the alternating patterns
+    pub left: bool,
+    // seconds before the next trigger pull
+    pub cooldown: f32,
+    // running for the current weapon only, cycling cancels it
+    pub reload: Option<Timer>,
+    pub empty_sound: Handle<AudioSource>,
+}
+
+impl Arsenal {
+    pub fn weapon(&self) -> &Weapon {
+        &self.weapons[self.current]
+    }
+
+    pub fn weapon_mut(&mut self) -> &mut Weapon {
+        &mut self.weapons[self.current]
+    }
+
+    pub fn cycle(&mut self) {
+        self.current = (self.current + 1) % self.weapons.len();
+        self.reload = None;
+    }
+
+    /// Starts reloading the current weapon, unless full, dry or already reloading
+    pub fn start_reload(&mut self) {
+        let weapon = self.weapon();
+        if self.reload.is_none() && weapon.rounds < weapon.magazine && weapon.reserve > 0 {
+            self.reload = Some(Timer::from_seconds(weapon.reload_time, TimerMode::Once));
+        }
+    }
+
+    /// Moves rounds from the reserve to the magazine of the current weapon
+    pub fn finish_reload(&mut self) {
+        self.reload = None;
+        let weapon = self.weapon_mut();
+        let moved = (weapon.magazine - weapon.rounds).min(weapon.reserve);
+        weapon.rounds += moved;
+        weapon.reserve -= moved;
+    }
+
+    /// Gives back `fraction` of the full reserve of every weapon
+    pub fn refill(&mut self, fraction: f32) {
+        for weapon in &mut self.weapons {
+            let amount = (weapon.max_reserve as f32 * fraction).ceil() as usize;
+            weapon.reserve = (weapon.reserve + amount).min(weapon.max_reserve);
+        }
+    }
+
+    /// Full magazines and reserves, as at the start of a run
+    pub fn restock(&mut self) {
+        for weapon in &mut self.weapons {
+            weapon.rounds = weapon.magazine;
+            weapon.reserve = weapon.max_reserve;
+        }
+        self.current = 0;
+        self.cooldown = 0.0;
+        self.reload = None;
+    }
+}
+
+/// Heat gauge shared by the guns, from 0 to `MAX_HEAT`
+#[derive(Resource, Default)]
+pub struct GunHeat {
+    pub value: f32,
+    // guns are locked until the gauge is back under the recover level
+    pub overheated: bool,
+    pub overheat_sound: Handle<AudioSource>,
+}
+
+pub const MAX_HEAT: f32 = 100.0;
+
+impl GunHeat {
+    /// Adds the heat of a trigger pull, true if it just overheated
+    pub fn add(&mut self, heat: f32) -> bool {
+        self.value = (self.value + heat).min(MAX_HEAT);
+        if self.value >= MAX_HEAT && !self.overheated {
+            self.overheated = true;
+            return true;
+        }
+        false
+    }
+
+    pub fn cool(&mut self, amount: f32, recover: f32) {
+        self.value = (self.value - amount).max(0.0);
+        if self.overheated && self.value <= recover {
+            self.overheated = false;
+        }
+    }
+}
+
+pub fn setup_arsenal(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    asset_server: Res<AssetServer>,
+    gameconfig: Res<GameConfig>,
+) {
+    let laser_mesh = meshes.add(Sphere::new(0.05).mesh());
+    let plasma_mesh = meshes.add(Sphere::new(0.15).mesh());
+    let missile_mesh = meshes.add(Cuboid::new(0.08, 0.08, 0.4).mesh());
+
+    let weapons = gameconfig
+        .ship
+        .weapons
+        .iter()
+        .map(|config: &WeaponConfig| {
+            let projectile =
+                ProjectileKind::parse(&config.projectile).unwrap_or(ProjectileKind::Laser);
+            let color = Color::srgb(config.color.x, config.color.y, config.color.z);
+
+            Weapon {
+                name: config.name.clone(),
+                projectile,
+                pattern: FirePattern::parse(&config.pattern).unwrap_or(FirePattern::Alternate),
+                speed: config.speed,
+                damage: config.damage,
+                radius: config.radius,
+                color,
+                spread_count: config.spread_count,
+                spread_angle: config.spread_angle.to_radians(),
+                fire_rate: config.fire_rate,
+                heat: config.heat,
+                range: config.range,
+                magazine: config.magazine,
+                max_reserve: config.reserve,
+                reload_time: config.reload_time,
+                rounds: config.magazine,
+                reserve: config.reserve,
+                mesh: match projectile {
+                    ProjectileKind::Laser | ProjectileKind::Beam => laser_mesh.clone(),
+                    ProjectileKind::Plasma => plasma_mesh.clone(),
+                    ProjectileKind::Missile => missile_mesh.clone(),
+                },
+                material: materials.add(StandardMaterial {
+                    base_color: color,
+                    emissive: color.to_linear() * 15.0,
+                    ..default()
+                }),
+                sounds: config
+                    .sounds
+                    .iter()
+                    .map(|path| asset_server.load(path))
+                    .collect(),
+            }
+        })
+        .collect();
+
+    commands.insert_resource(Arsenal {
+        weapons,
+        current: 0,
+        left: true,
+        cooldown: 0.0,
+        reload: None,
+        empty_sound: asset_server.load(gameconfig.ship.ammo.empty_sound.clone()),
+    });
+    commands.insert_resource(GunHeat {
+        overheat_sound: asset_server.load(gameconfig.ship.heat.overheat_sound.clone()),
+        ..default()
+    });
+}
+
+pub fn reload_weapon(
+    time: Res<Time>,
+    keybinds: Res<Keybinds>,
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mouse: Res<ButtonInput<MouseButton>>,
+    mut arsenal: ResMut<Arsenal>,
+) {
+    if keybinds.reload.just_pressed(&keyboard, &mouse) {
+        arsenal.start_reload();
+    }
+
+    let finished = arsenal
+        .reload
+        .as_mut()
+        .is_some_and(|timer| timer.tick(time.delta()).is_finished());
+    if finished {
+        arsenal.finish_reload();
+    }
+}
+
+pub fn cool_guns(
+    time: Res<Time>,
+    gameconfig: Res<GameConfig>,
+    mut arsenal: ResMut<Arsenal>,
+    mut heat: ResMut<GunHeat>,
+) {
+    let dt = time.delta_secs();
+    arsenal.cooldown = (arsenal.cooldown - dt).max(0.0);
+    heat.cool(
+        gameconfig.ship.heat.cooling * dt,
+        gameconfig.ship.heat.recover,
+    );
+}
+
+pub fn cycle_weapon(
+    keybinds: Res<Keybinds>,
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mouse: Res<ButtonInput<MouseButton>>,
+    mut arsenal: ResMut<Arsenal>,
+) {
+    if keybinds.cycle_weapon.just_pressed(&keyboard, &mouse) {
+        arsenal.cycle();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn overheat_locks_until_recovered() {
+        let mut heat = GunHeat::default();
+        assert!(!heat.add(60.0));
+        assert!(heat.add(60.0));
+        assert_eq!(heat.value, MAX_HEAT);
+        // already overheated, no second cue
+        assert!(!heat.add(10.0));
+
+        heat.cool(50.0, 40.0);
+        assert!(heat.overheated);
+        heat.cool(10.0, 40.0);
+        assert!(!heat.overheated);
+    }
+}
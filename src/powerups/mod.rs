@@ -0,0 +1,233 @@
+use bevy::prelude::*;
+
+use crate::config::structs::{GameConfig, PowerUpConfig};
+use crate::game_over::GameOverState;
+use crate::game_states::GameState;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum PowerUpKind {
+    /// No damage from impacts
+    Shield,
+    /// Shorter delay between trigger pulls
+    RapidFire,
+    /// Extra projectiles fanned around each shot
+    SpreadShot,
+    /// Points scored are multiplied
+    ScoreMultiplier,
+}
+
+impl PowerUpKind {
+    pub const ALL: [PowerUpKind; 4] = [
+        PowerUpKind::Shield,
+        PowerUpKind::RapidFire,
+        PowerUpKind::SpreadShot,
+        PowerUpKind::ScoreMultiplier,
+    ];
+
+    /// Name used by the manifest, for the effects and the drop tables
+    pub fn name(&self) -> &'static str {
+        match self {
+            PowerUpKind::Shield => "shield_boost",
+            PowerUpKind::RapidFire => "rapid_fire",
+            PowerUpKind::SpreadShot => "spread_shot",
+            PowerUpKind::ScoreMultiplier => "score_multiplier",
+        }
+    }
+
+    pub fn parse(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|kind| kind.name() == name)
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            PowerUpKind::Shield => "BARRIER",
+            PowerUpKind::RapidFire => "RAPID",
+            PowerUpKind::SpreadShot => "SPREAD",
+            PowerUpKind::ScoreMultiplier => "SCORE",
+        }
+    }
+
+    pub fn color(&self) -> Color {
+        match self {
+            PowerUpKind::Shield => Color::srgb(0.6, 0.9, 1.0),
+            PowerUpKind::RapidFire => Color::srgb(1.0, 0.4, 0.8),
+            PowerUpKind::SpreadShot => Color::srgb(0.7, 0.3, 1.0),
+            PowerUpKind::ScoreMultiplier => Color::srgb(1.0, 1.0, 0.4),
+        }
+    }
+}
+
+/// How a power-up collected while already active adds up
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Stacking {
+    /// Restarts the timer
+    Refresh,
+    /// Adds the duration, up to `max_duration`
+    Extend,
+    /// Adds a stack up to `max_stacks` and restarts the timer
+    Intensity,
+}
+
+impl Stacking {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "refresh" => Some(Stacking::Refresh),
+            "extend" => Some(Stacking::Extend),
+            "intensity" => Some(Stacking::Intensity),
+            _ => None,
+        }
+    }
+}
+
+pub struct ActivePowerUp {
+    pub kind: PowerUpKind,
+    // seconds
+    pub remaining: f32,
+    pub stacks: u32,
+    pub strength: f32,
+    // radians
+    pub angle: f32,
+}
+
+/// Power-ups running on the ship
+#[derive(Resource, Default)]
+pub struct PowerUps {
+    pub active: Vec<ActivePowerUp>,
+}
+
+impl PowerUps {
+    pub fn get(&self, kind: PowerUpKind) -> Option<&ActivePowerUp> {
+        self.active.iter().find(|powerup| powerup.kind == kind)
+    }
+
+    pub fn activate(&mut self, kind: PowerUpKind, config: &PowerUpConfig) {
+        let Some(powerup) = self.active.iter_mut().find(|powerup| powerup.kind == kind) else {
+            self.active.push(ActivePowerUp {
+                kind,
+                remaining: config.duration,
+                stacks: 1,
+                strength: config.strength,
+                angle: config.angle.to_radians(),
+            });
+            return;
+        };
+
+        match Stacking::parse(&config.stacking).unwrap_or(Stacking::Refresh) {
+            Stacking::Refresh => powerup.remaining = config.duration,
+            Stacking::Extend => {
+                powerup.remaining = (powerup.remaining + config.duration).min(config.max_duration)
+            }
+            Stacking::Intensity => {
+                powerup.stacks = (powerup.stacks + 1).min(config.max_stacks);
+                powerup.remaining = config.duration;
+            }
+        }
+    }
+
+    pub fn tick(&mut self, seconds: f32) {
+        for powerup in &mut self.active {
+            powerup.remaining -= seconds;
+        }
+        self.active.retain(|powerup| powerup.remaining > 0.0);
+    }
+
+    pub fn shielded(&self) -> bool {
+        self.get(PowerUpKind::Shield).is_some()
+    }
+
+    pub fn fire_rate_multiplier(&self) -> f32 {
+        self.multiplier(PowerUpKind::RapidFire)
+    }
+
+    pub fn score_multiplier(&self) -> f32 {
+        self.multiplier(PowerUpKind::ScoreMultiplier)
+    }
+
+    /// Extra projectiles per shot and the full angle they are fanned over
+    pub fn extra_spread(&self) -> (usize, f32) {
+        self.get(PowerUpKind::SpreadShot)
+            .map(|powerup| {
+                (
+                    (powerup.strength * powerup.stacks as f32) as usize,
+                    powerup.angle,
+                )
+            })
+            .unwrap_or((0, 0.0))
+    }
+
+    // each stack adds the bonus of one power-up, x2 twice is x3
+    fn multiplier(&self, kind: PowerUpKind) -> f32 {
+        self.get(kind)
+            .map(|powerup| 1.0 + (powerup.strength - 1.0) * powerup.stacks as f32)
+            .unwrap_or(1.0)
+    }
+}
+
+pub fn plugin(app: &mut App) {
+    app.init_resource::<PowerUps>().add_systems(
+        Update,
+        tick_powerups
+            .run_if(in_state(GameState::Game))
+            .run_if(in_state(GameOverState::None)),
+    );
+}
+
+fn tick_powerups(time: Res<Time>, mut powerups: ResMut<PowerUps>) {
+    if !powerups.active.is_empty() {
+        powerups.tick(time.delta_secs());
+    }
+}
+
+/// Effect settings of `kind`, neutral if the manifest has none
+pub fn config(gameconfig: &GameConfig, kind: PowerUpKind) -> PowerUpConfig {
+    gameconfig
+        .powerups
+        .get(kind.name())
+        .cloned()
+        .unwrap_or(PowerUpConfig {
+            strength: 1.0,
+            max_stacks: 1,
+            ..default()
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(stacking: &str) -> PowerUpConfig {
+        PowerUpConfig {
+            duration: 10.0,
+            strength: 2.0,
+            stacking: stacking.to_string(),
+            max_stacks: 3,
+            max_duration: 15.0,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn stacking_rules() {
+        let mut powerups = PowerUps::default();
+        let kind = PowerUpKind::RapidFire;
+
+        powerups.activate(kind, &config("extend"));
+        powerups.tick(4.0);
+        powerups.activate(kind, &config("extend"));
+        assert_eq!(powerups.get(kind).unwrap().remaining, 15.0);
+
+        powerups.activate(kind, &config("refresh"));
+        assert_eq!(powerups.get(kind).unwrap().remaining, 10.0);
+        assert_eq!(powerups.fire_rate_multiplier(), 2.0);
+
+        for _ in 0..4 {
+            powerups.activate(kind, &config("intensity"));
+        }
+        assert_eq!(powerups.get(kind).unwrap().stacks, 3);
+        assert_eq!(powerups.fire_rate_multiplier(), 4.0);
+
+        powerups.tick(10.0);
+        assert!(powerups.active.is_empty());
+        assert_eq!(powerups.fire_rate_multiplier(), 1.0);
+    }
+}
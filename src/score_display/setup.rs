@@ -39,6 +39,7 @@ pub fn setup_score(
                 height: Val::Percent(100.0),
                 justify_content: JustifyContent::Center,
                 align_items: AlignItems::Center,
+                flex_direction: FlexDirection::Column,
                 ..default()
             },
             UiTargetCamera(cam_entity),
@@ -58,6 +59,39 @@ pub fn setup_score(
                 },
                 ScoreText,
             ));
+
+            parent.spawn((
+                Text::new(""),
+                TextFont {
+                    font: font.clone(),
+                    font_size: 60.0,
+                    ..default()
+                },
+                TextColor(Color::srgb(1.0, 0.75, 0.2)),
+                InventoryText,
+            ));
+
+            parent.spawn((
+                Text::new(""),
+                TextFont {
+                    font: font.clone(),
+                    font_size: 60.0,
+                    ..default()
+                },
+                TextColor(Color::srgb(0.2, 0.6, 1.0)),
+                HealthText,
+            ));
+
+            parent.spawn((
+                Text::new(""),
+                TextFont {
+                    font: font.clone(),
+                    font_size: 60.0,
+                    ..default()
+                },
+                TextColor(Color::srgb(1.0, 0.4, 0.8)),
+                PowerUpText,
+            ));
         });
 }
 
@@ -18,3 +18,12 @@ pub struct ScoreCamTimer {
 
 #[derive(Component)]
 pub struct ScoreText;
+
+#[derive(Component)]
+pub struct InventoryText;
+
+#[derive(Component)]
+pub struct HealthText;
+
+#[derive(Component)]
+pub struct PowerUpText;
@@ -1,11 +1,32 @@
+use crate::controller::structs::Player;
+use crate::pickups::{PickupInventory, PickupKind};
+use crate::player::health::ShipHealth;
+use crate::powerups::PowerUps;
 use crate::{globals_structs::Score, score_display::structs::*};
 use bevy::prelude::*;
 
 pub fn toggle_screenshot_camera(
     time: Res<Time>,
     score: Res<Score>,
+    inventory: Res<PickupInventory>,
+    powerups: Res<PowerUps>,
     mut query: Query<(&mut Camera, &mut ScoreCamTimer)>,
     mut query_score: Query<(&mut Text, &ScoreText)>,
+    mut query_inventory: Query<&mut Text, (With<InventoryText>, Without<ScoreText>)>,
+    mut query_health: Query<
+        &mut Text,
+        (With<HealthText>, Without<InventoryText>, Without<ScoreText>),
+    >,
+    mut query_powerups: Query<
+        &mut Text,
+        (
+            With<PowerUpText>,
+            Without<HealthText>,
+            Without<InventoryText>,
+            Without<ScoreText>,
+        ),
+    >,
+    health: Single<&ShipHealth, With<Player>>,
 ) {
     for (mut camera, mut sc) in &mut query {
         sc.timer.tick(time.delta());
@@ -15,6 +36,43 @@ pub fn toggle_screenshot_camera(
             for (mut text, _) in &mut query_score {
                 *text = Text::new(format!("{} $", (*score).value));
             }
+            for mut text in &mut query_inventory {
+                *text = Text::new(
+                    PickupKind::ALL
+                        .iter()
+                        // power-ups are listed with their timers
+                        .filter(|kind| !matches!(kind, PickupKind::PowerUp(_)))
+                        .map(|kind| format!("{} {}", kind.label(), inventory.count(*kind)))
+                        .collect::<Vec<_>>()
+                        .join("  "),
+                );
+            }
+            for mut text in &mut query_health {
+                *text = Text::new(format!(
+                    "HULL {:.0}  SHD {:.0}",
+                    health.hull.ceil(),
+                    health.shield.ceil()
+                ));
+            }
+            for mut text in &mut query_powerups {
+                *text = Text::new(
+                    powerups
+                        .active
+                        .iter()
+                        .map(|powerup| match powerup.stacks {
+                            1 => {
+                                format!("{} {:.0}s", powerup.kind.label(), powerup.remaining.ceil())
+                            }
+                            stacks => format!(
+                                "{} x{stacks} {:.0}s",
+                                powerup.kind.label(),
+                                powerup.remaining.ceil()
+                            ),
+                        })
+                        .collect::<Vec<_>>()
+                        .join("  "),
+                );
+            }
         } else {
             camera.is_active = false;
         }
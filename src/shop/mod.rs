@@ -0,0 +1,269 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use bevy::prelude::*;
+
+use crate::config::structs::{GameConfig, ShipConfig, ShopConfig, UpgradeConfig};
+use crate::controller::structs::Player;
+use crate::game_over::GameOverState;
+use crate::game_states::GameState;
+use crate::globals_structs::Score;
+use crate::menu::structs::MenuState;
+use crate::player::health::ShipHealth;
+use crate::player::weapons::Arsenal;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum UpgradeKind {
+    Speed,
+    Rotation,
+    /// Damage of every weapon
+    Weapons,
+    Hull,
+    Shield,
+}
+
+impl UpgradeKind {
+    pub const ALL: [UpgradeKind; 5] = [
+        UpgradeKind::Speed,
+        UpgradeKind::Rotation,
+        UpgradeKind::Weapons,
+        UpgradeKind::Hull,
+        UpgradeKind::Shield,
+    ];
+
+    /// Name used by the manifest and the save file
+    pub fn name(&self) -> &'static str {
+        match self {
+            UpgradeKind::Speed => "speed",
+            UpgradeKind::Rotation => "rotation",
+            UpgradeKind::Weapons => "weapons",
+            UpgradeKind::Hull => "hull",
+            UpgradeKind::Shield => "shield",
+        }
+    }
+
+    pub fn parse(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|kind| kind.name() == name)
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            UpgradeKind::Speed => "Thrusters",
+            UpgradeKind::Rotation => "Gyroscopes",
+            UpgradeKind::Weapons => "Weapons",
+            UpgradeKind::Hull => "Hull",
+            UpgradeKind::Shield => "Shield",
+        }
+    }
+}
+
+/// Credit balance and upgrade levels, kept on disk between runs
+#[derive(Resource, Default, Debug, PartialEq)]
+pub struct Upgrades {
+    pub credits: u32,
+    levels: HashMap<UpgradeKind, u32>,
+}
+
+impl Upgrades {
+    pub fn level(&self, kind: UpgradeKind) -> u32 {
+        self.levels.get(&kind).copied().unwrap_or(0)
+    }
+
+    /// Price of the next level, `None` once maxed out
+    pub fn price(&self, kind: UpgradeKind, shop: &ShopConfig) -> Option<u32> {
+        let config = upgrade_config(shop, kind);
+        let level = self.level(kind);
+        (level < config.max_level)
+            .then(|| (config.cost * config.cost_growth.powi(level as i32)).round() as u32)
+    }
+
+    /// Spends the credits for the next level of `kind`, false if it can't be afforded
+    pub fn buy(&mut self, kind: UpgradeKind, shop: &ShopConfig) -> bool {
+        let Some(price) = self
+            .price(kind, shop)
+            .filter(|price| *price <= self.credits)
+        else {
+            return false;
+        };
+        self.credits -= price;
+        *self.levels.entry(kind).or_default() += 1;
+        true
+    }
+
+    /// Reads `path`, a missing or unreadable save starts from scratch
+    pub fn load(path: &str) -> Self {
+        let mut upgrades = Upgrades::default();
+        let Ok(content) = fs::read_to_string(path) else {
+            return upgrades;
+        };
+
+        for line in content.lines() {
+            let Some((key, value)) = line.split_once('=') else {
+                continue;
+            };
+            let Ok(value) = value.trim().parse::<u32>() else {
+                continue;
+            };
+            match (key.trim(), UpgradeKind::parse(key.trim())) {
+                ("credits", _) => upgrades.credits = value,
+                (_, Some(kind)) if value > 0 => {
+                    upgrades.levels.insert(kind, value);
+                }
+                _ => {}
+            }
+        }
+        upgrades
+    }
+
+    pub fn save(&self, path: &str) -> std::io::Result<()> {
+        if let Some(dir) = Path::new(path).parent() {
+            fs::create_dir_all(dir)?;
+        }
+
+        let mut content = format!("credits = {}\n", self.credits);
+        for kind in UpgradeKind::ALL {
+            content += &format!("{} = {}\n", kind.name(), self.level(kind));
+        }
+        fs::write(path, content)
+    }
+}
+
+/// Ship as the manifest describes it, and whether the current run flies with the latest upgrades
+#[derive(Resource)]
+pub struct Hangar {
+    pub base: ShipConfig,
+    pub fitted: bool,
+}
+
+/// Upgrade settings of `kind`, free of effect if the manifest has none
+pub fn upgrade_config(shop: &ShopConfig, kind: UpgradeKind) -> UpgradeConfig {
+    shop.upgrades.get(kind.name()).cloned().unwrap_or_default()
+}
+
+/// `base` with every upgrade level applied
+pub fn refit(base: &ShipConfig, shop: &ShopConfig, upgrades: &Upgrades) -> ShipConfig {
+    let factor = |kind| 1.0 + upgrade_config(shop, kind).bonus * upgrades.level(kind) as f32;
+
+    let mut ship = base.clone();
+    ship.speed *= factor(UpgradeKind::Speed);
+    ship.rotation_speed *= factor(UpgradeKind::Rotation);
+    for weapon in &mut ship.weapons {
+        weapon.damage *= factor(UpgradeKind::Weapons);
+    }
+    ship.damage.hull *= factor(UpgradeKind::Hull);
+    ship.damage.shield *= factor(UpgradeKind::Shield);
+    ship
+}
+
+pub fn save_upgrades(gameconfig: &GameConfig, upgrades: &Upgrades) {
+    if gameconfig.shop.save.is_empty() {
+        return;
+    }
+    if let Err(e) = upgrades.save(&gameconfig.shop.save) {
+        warn!("Cannot save the upgrades to {}: {e}", gameconfig.shop.save);
+    }
+}
+
+pub fn plugin(app: &mut App) {
+    app.add_systems(PreStartup, load_upgrades)
+        .add_systems(OnEnter(GameOverState::Menu), bank_credits)
+        .add_systems(OnExit(MenuState::GameOver), end_run)
+        .add_systems(OnExit(GameState::Menu), fit_ship);
+}
+
+// before the startup systems build the ship and its arsenal from the config
+fn load_upgrades(mut commands: Commands, mut gameconfig: ResMut<GameConfig>) {
+    let upgrades = if gameconfig.shop.save.is_empty() {
+        Upgrades::default()
+    } else {
+        Upgrades::load(&gameconfig.shop.save)
+    };
+
+    let base = gameconfig.ship.clone();
+    gameconfig.ship = refit(&base, &gameconfig.shop, &upgrades);
+
+    commands.insert_resource(Hangar { base, fitted: true });
+    commands.insert_resource(upgrades);
+}
+
+fn bank_credits(score: Res<Score>, gameconfig: Res<GameConfig>, mut upgrades: ResMut<Upgrades>) {
+    upgrades.credits += score.value;
+    save_upgrades(&gameconfig, &upgrades);
+}
+
+fn end_run(mut hangar: ResMut<Hangar>) {
+    hangar.fitted = false;
+}
+
+/// Applies the upgrades bought since the last run when the next one launches
+fn fit_ship(
+    mut hangar: ResMut<Hangar>,
+    mut gameconfig: ResMut<GameConfig>,
+    upgrades: Res<Upgrades>,
+    mut arsenal: ResMut<Arsenal>,
+    mut health: Single<&mut ShipHealth, With<Player>>,
+) {
+    if hangar.fitted {
+        return;
+    }
+    hangar.fitted = true;
+
+    gameconfig.ship = refit(&hangar.base, &gameconfig.shop, &upgrades);
+    for (weapon, config) in arsenal.weapons.iter_mut().zip(&gameconfig.ship.weapons) {
+        weapon.damage = config.damage;
+    }
+    **health = ShipHealth::new(&gameconfig.ship.damage);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn prices_grow_and_levels_cap() {
+        let mut shop = ShopConfig::default();
+        shop.upgrades.insert(
+            "hull".to_string(),
+            UpgradeConfig {
+                cost: 100.0,
+                cost_growth: 2.0,
+                bonus: 0.5,
+                max_level: 2,
+            },
+        );
+        let mut upgrades = Upgrades {
+            credits: 250,
+            ..default()
+        };
+
+        assert!(upgrades.buy(UpgradeKind::Hull, &shop));
+        assert_eq!(upgrades.price(UpgradeKind::Hull, &shop), Some(200));
+        assert!(!upgrades.buy(UpgradeKind::Hull, &shop));
+        upgrades.credits += 50;
+        assert!(upgrades.buy(UpgradeKind::Hull, &shop));
+        assert_eq!(upgrades.price(UpgradeKind::Hull, &shop), None);
+        assert_eq!(upgrades.credits, 0);
+
+        let mut base = ShipConfig::default();
+        base.damage.hull = 100.0;
+        assert_eq!(refit(&base, &shop, &upgrades).damage.hull, 200.0);
+        // no manifest entry, no effect
+        assert!(!upgrades.buy(UpgradeKind::Speed, &shop));
+    }
+
+    #[test]
+    fn save_round_trip() {
+        let path = std::env::temp_dir().join("ast3roid_upgrades_test.txt");
+        let path = path.to_str().unwrap();
+        let mut upgrades = Upgrades {
+            credits: 1234,
+            ..default()
+        };
+        upgrades.levels.insert(UpgradeKind::Weapons, 3);
+
+        upgrades.save(path).unwrap();
+        assert_eq!(Upgrades::load(path), upgrades);
+        let _ = fs::remove_file(path);
+    }
+}
@@ -0,0 +1,200 @@
+use bevy::prelude::*;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+use super::TestApp;
+use crate::asteroids::fragmentation::fragment;
+use crate::asteroids::messages::AsteroidDestroyed;
+use crate::asteroids::{Asteroid, DespawnAnimation, Sun};
+use crate::globals_structs::Score;
+use crate::physics::gravity::GravitySource;
+use crate::physics::Velocity;
+use crate::player::Ammo;
+
+#[test]
+fn fragments_keep_volume_and_momentum() {
+    let app = TestApp::new();
+    let config = app.config().asteroids.fragmentation.clone();
+    let mut rng = StdRng::seed_from_u64(7);
+
+    let (size, velocity, impulse) = (6.0_f32, Vec3::new(1.0, 0.0, -2.0), Vec3::X * 50.0);
+    for _ in 0..20 {
+        let fragments = fragment(
+            size,
+            velocity,
+            Vec3::Y,
+            Vec3::Z * size,
+            impulse,
+            &config,
+            &mut rng,
+        );
+        assert!(fragments.len() >= config.min_fragments);
+
+        let volume: f32 = fragments.iter().map(|frag| frag.size.powi(3)).sum();
+        assert!((volume - size.powi(3)).abs() < 1e-2, "volume {volume}");
+
+        let momentum: Vec3 = fragments
+            .iter()
+            .map(|frag| frag.velocity * frag.size.powi(3))
+            .sum();
+        let expected = velocity * size.powi(3) + impulse;
+        assert!(
+            momentum.distance(expected) < 1e-1,
+            "momentum {momentum}, expected {expected}"
+        );
+    }
+}
+
+#[test]
+fn shot_asteroid_splits() {
+    let mut app = TestApp::new();
+    let size = 6.0;
+    let parent = app.spawn_asteroid(size, Vec3::new(0.0, 0.0, -30.0), Vec3::ZERO);
+    app.world().spawn((
+        Ammo {
+            damage: 100.0,
+            radius: 1.0,
+            in_flight: true,
+        },
+        Transform::from_xyz(0.0, 0.0, -5.0),
+        Velocity(Vec3::NEG_Z * 300.0),
+    ));
+
+    app.step(10);
+    assert!(app.world().get::<DespawnAnimation>(parent).is_some());
+    assert!(app.world().resource::<Score>().value > 0);
+
+    // the parent is gone once its animation is over
+    app.step_secs(1.0);
+    assert!(app.world().get_entity(parent).is_err());
+
+    let mut query = app.world().query::<&Asteroid>();
+    let sizes: Vec<f32> = query.iter(app.app.world()).map(|a| a.size).collect();
+    assert!(!sizes.is_empty());
+    assert!(sizes.iter().all(|&fragment_size| fragment_size < size));
+    // debris took the smallest pieces away
+    assert!(app.asteroid_volume() <= size.powi(3) + 1e-2);
+}
+
+#[test]
+fn weak_shot_only_pushes() {
+    let mut app = TestApp::new();
+    let rock = app.spawn_asteroid(6.0, Vec3::new(0.0, 0.0, -30.0), Vec3::ZERO);
+    app.world().spawn((
+        Ammo {
+            damage: 1.0,
+            radius: 1.0,
+            in_flight: true,
+        },
+        Transform::from_xyz(0.0, 0.0, -5.0),
+        Velocity(Vec3::NEG_Z * 300.0),
+    ));
+
+    app.step(10);
+    assert!(app.world().get::<DespawnAnimation>(rock).is_none());
+    assert!(app.world().get::<Velocity>(rock).unwrap().0.z < 0.0);
+    assert_eq!(app.world().resource::<Score>().value, 0);
+}
+
+#[test]
+fn violent_impact_shatters_the_smaller_rock() {
+    let mut app = TestApp::new();
+    let big = app.spawn_asteroid(5.0, Vec3::ZERO, Vec3::ZERO);
+    let small = app.spawn_asteroid(2.0, Vec3::new(0.0, 0.0, -7.5), Vec3::Z * 20.0);
+
+    app.step(2);
+    assert!(app.world().get::<DespawnAnimation>(small).is_some());
+    assert!(app.world().get::<DespawnAnimation>(big).is_none());
+    // nobody shot, nothing to score
+    assert_eq!(app.world().resource::<Score>().value, 0);
+}
+
+#[test]
+fn slow_touch_merges_small_rocks() {
+    let mut app = TestApp::new();
+    let a = app.spawn_asteroid(2.0, Vec3::ZERO, Vec3::Z * 0.1);
+    let b = app.spawn_asteroid(2.0, Vec3::new(0.0, 0.0, 3.9), Vec3::ZERO);
+    let volume = app.asteroid_volume();
+
+    app.step(1);
+    assert!(app.world().get_entity(a).is_err());
+    assert!(app.world().get_entity(b).is_err());
+
+    let mut query = app.world().query::<&Asteroid>();
+    assert_eq!(query.iter(app.app.world()).count(), 1);
+    assert!((app.asteroid_volume() - volume).abs() < 1e-3);
+}
+
+#[test]
+fn split_suns_keep_their_gravity() {
+    let mut app = TestApp::new();
+    let mut suns = app.world().query::<(&Transform, &Sun)>();
+    let (sun_position, sun_size) = suns
+        .single(app.app.world())
+        .map(|(transform, sun)| (transform.translation, sun.size))
+        .unwrap();
+    let ship = app.spawn_ship(sun_position + Vec3::Z * (sun_size + 0.5));
+    app.world().get_mut::<Velocity>(ship).unwrap().0 = Vec3::NEG_Z * 10.0;
+
+    app.step(2);
+
+    let mut split = app
+        .world()
+        .query_filtered::<(&Sun, Has<GravitySource>), Without<DespawnAnimation>>();
+    let split: Vec<(f32, bool)> = split
+        .iter(app.app.world())
+        .map(|(sun, gravity)| (sun.size, gravity))
+        .collect();
+    assert_eq!(split, vec![(sun_size / 2.0, true); 2]);
+}
+
+#[test]
+fn merged_rock_is_not_shot_in_the_same_tick() {
+    let mut app = TestApp::new();
+    let a = app.spawn_asteroid(2.0, Vec3::ZERO, Vec3::Z * 0.1);
+    app.spawn_asteroid(2.0, Vec3::new(0.0, 0.0, 3.9), Vec3::ZERO);
+    app.world().spawn((
+        Ammo {
+            damage: 100.0,
+            radius: 1.0,
+            in_flight: true,
+        },
+        Transform::from_xyz(-3.5, 0.0, 0.0),
+        Velocity(Vec3::X * 300.0),
+    ));
+    let mut cursor = app
+        .world()
+        .resource::<Messages<AsteroidDestroyed>>()
+        .get_cursor();
+
+    app.step(1);
+    assert!(app.world().get_entity(a).is_err());
+    let messages = app.world().resource::<Messages<AsteroidDestroyed>>();
+    assert_eq!(
+        cursor
+            .read(messages)
+            .filter(|message| message.asteroid == a)
+            .count(),
+        0
+    );
+}
+
+#[test]
+fn hitboxes_turn_with_the_ship() {
+    let mut app = TestApp::new();
+    let ship = app.spawn_ship(Vec3::ZERO);
+    // nose to the -X side, only the nose hitbox reaches the rock
+    app.world().get_mut::<Transform>(ship).unwrap().rotation =
+        Quat::from_rotation_y(std::f32::consts::FRAC_PI_2);
+    let nose = app.config().ship.hitboxes.shapes[0].clone();
+    let size = 1.0;
+    let rock = Vec3::new(
+        nose.center.z - nose.radius - size + 0.25,
+        nose.center.y,
+        0.0,
+    );
+    app.spawn_asteroid(size, rock, Vec3::ZERO);
+    app.step(1);
+
+    assert_ne!(**app.world().get::<Velocity>(ship).unwrap(), Vec3::ZERO);
+}
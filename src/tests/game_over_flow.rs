@@ -0,0 +1,123 @@
+use bevy::prelude::*;
+
+use super::{impact, TestApp};
+use crate::asteroids::messages::ShipImpact;
+use crate::game_over::GameOverState;
+use crate::game_states::GameState;
+use crate::globals_structs::Score;
+use crate::menu::structs::MenuState;
+use crate::physics::RotationVelocity;
+use crate::player::health::ShipHealth;
+use crate::powerups::{self, PowerUpKind, PowerUps};
+use crate::shop::{UpgradeKind, Upgrades};
+
+/// Impact strong enough to go through the shield and the hull at once
+fn lethal_impact(app: &TestApp, ship: Entity, asteroid: Entity) -> ShipImpact {
+    let damage = &app.config().ship.damage;
+    let amount = (damage.hull + damage.shield) * 2.0;
+    ShipImpact {
+        asteroid,
+        size_type: "XL",
+        ..impact(
+            ship,
+            damage.impulse_threshold + amount / damage.damage_per_impulse,
+        )
+    }
+}
+
+#[test]
+fn destroyed_ship_drifts_to_the_menu() {
+    let mut app = TestApp::new();
+    let ship = app.spawn_ship(Vec3::ZERO);
+    let asteroid = app.spawn_asteroid(20.0, Vec3::new(0.0, 0.0, -200.0), Vec3::ZERO);
+    app.step(1);
+
+    let impact = lethal_impact(&app, ship, asteroid);
+    app.world().write_message(impact);
+    app.step(2);
+
+    assert!(app.world().get::<ShipHealth>(ship).unwrap().is_destroyed());
+    assert_eq!(app.state::<GameOverState>(), GameOverState::Drift);
+    assert_ne!(
+        **app.world().get::<RotationVelocity>(ship).unwrap(),
+        Vec3::ZERO
+    );
+    assert_eq!(app.state::<GameState>(), GameState::Game);
+
+    app.step_secs(3.5);
+    assert_eq!(app.state::<GameOverState>(), GameOverState::Menu);
+    assert_eq!(app.state::<GameState>(), GameState::Menu);
+    assert_eq!(app.state::<MenuState>(), MenuState::GameOver);
+
+    // leaving the game over screen repairs the ship for the next run
+    app.world()
+        .resource_mut::<NextState<MenuState>>()
+        .set(MenuState::Main);
+    app.step(2);
+    assert_eq!(app.state::<GameOverState>(), GameOverState::None);
+    let hull = app.config().ship.damage.hull;
+    assert_eq!(app.world().get::<ShipHealth>(ship).unwrap().hull, hull);
+}
+
+#[test]
+fn shield_power_up_absorbs_a_lethal_hit() {
+    let mut app = TestApp::new();
+    let ship = app.spawn_ship(Vec3::ZERO);
+    let asteroid = app.spawn_asteroid(20.0, Vec3::new(0.0, 0.0, -200.0), Vec3::ZERO);
+    let config = powerups::config(app.config(), PowerUpKind::Shield);
+    app.world()
+        .resource_mut::<PowerUps>()
+        .activate(PowerUpKind::Shield, &config);
+    app.step(1);
+
+    let impact = lethal_impact(&app, ship, asteroid);
+    app.world().write_message(impact);
+    app.step(2);
+
+    let hull = app.config().ship.damage.hull;
+    assert_eq!(app.world().get::<ShipHealth>(ship).unwrap().hull, hull);
+    assert_eq!(app.state::<GameOverState>(), GameOverState::None);
+
+    // once it runs out the ship is exposed again
+    app.step_secs(config.max_duration);
+    assert!(!app.world().resource::<PowerUps>().shielded());
+}
+
+#[test]
+fn banked_credits_buy_a_hull_fitted_at_the_next_launch() {
+    let mut app = TestApp::new();
+    let ship = app.spawn_ship(Vec3::ZERO);
+    let asteroid = app.spawn_asteroid(20.0, Vec3::new(0.0, 0.0, -200.0), Vec3::ZERO);
+    app.step(1);
+
+    app.world().resource_mut::<Score>().value = 10_000;
+    let impact = lethal_impact(&app, ship, asteroid);
+    app.world().write_message(impact);
+    app.step(2);
+    app.step_secs(3.5);
+    assert_eq!(app.state::<GameOverState>(), GameOverState::Menu);
+    assert_eq!(app.world().resource::<Upgrades>().credits, 10_000);
+
+    let base_hull = app.config().ship.damage.hull;
+    let shop = app.config().shop.clone();
+    assert!(app
+        .world()
+        .resource_mut::<Upgrades>()
+        .buy(UpgradeKind::Hull, &shop));
+
+    // bought for the next run, the one just lost is left as it was
+    app.world()
+        .resource_mut::<NextState<MenuState>>()
+        .set(MenuState::Main);
+    app.step(2);
+    assert_eq!(app.config().ship.damage.hull, base_hull);
+    assert_eq!(app.world().resource::<Score>().value, 0);
+
+    app.world()
+        .resource_mut::<NextState<GameState>>()
+        .set(GameState::Game);
+    app.step(2);
+    let hull = app.config().ship.damage.hull;
+    assert!(hull > base_hull);
+    assert_eq!(app.world().get::<ShipHealth>(ship).unwrap().hull, hull);
+}
@@ -0,0 +1,184 @@
+//! Headless harness for the gameplay plugins: no window, no renderer, no audio
+//! device. Asset types are registered so handles can be created, nothing is
+//! ever drawn or played.
+
+use std::time::Duration;
+
+use bevy::asset::AssetPlugin;
+use bevy::gizmos::GizmoAsset;
+use bevy::prelude::*;
+use bevy::state::app::StatesPlugin;
+use bevy::time::TimeUpdateStrategy;
+
+use crate::asteroids::messages::{AsteroidDestroyed, ShipImpact};
+use crate::asteroids::{self, Asteroid};
+use crate::config::{self, structs::GameConfig};
+use crate::controller::structs::{ControllerState, Player};
+use crate::floating_origin;
+use crate::game_over;
+use crate::game_states::GameState;
+use crate::globals_structs::{Keybinds, MusicVolume, Score};
+use crate::menu::structs::MenuState;
+use crate::physics::{self, PhysicsMaterial, RotationVelocity, Velocity};
+use crate::pickups::PickupInventory;
+use crate::player::{self, health::ShipHealth, hitbox::hitbox};
+use crate::powerups;
+use crate::shop;
+
+mod collisions;
+mod game_over_flow;
+mod origin;
+mod weapons;
+
+/// Length of a frame, the physics ticks at its own rate inside of it
+pub const FRAME: f32 = 1.0 / 60.0;
+
+pub struct TestApp {
+    pub app: App,
+}
+
+impl TestApp {
+    /// Gameplay plugins on top of `MinimalPlugins`, already in `GameState::Game`
+    pub fn new() -> Self {
+        let mut gameconfig = config::load_game_config("assets/manifest.xml");
+        // one mesh per size is plenty, generating them is the slow part of the startup
+        gameconfig.asteroids.generator.variants = 1;
+        // keeps the player's save out of the runs
+        gameconfig.shop.save = String::new();
+
+        let mut app = App::new();
+        app.add_plugins((
+            MinimalPlugins,
+            StatesPlugin,
+            AssetPlugin::default(),
+            TransformPlugin,
+        ))
+        .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_secs_f32(
+            FRAME,
+        )))
+        // rendering and audio stubs
+        .init_asset::<Mesh>()
+        .init_asset::<StandardMaterial>()
+        .init_asset::<Image>()
+        .init_asset::<TextureAtlasLayout>()
+        .init_asset::<AudioSource>()
+        .init_asset::<GizmoAsset>()
+        .init_gizmo_group::<DefaultGizmoConfigGroup>()
+        .init_resource::<ButtonInput<KeyCode>>()
+        .init_resource::<ButtonInput<MouseButton>>()
+        // what main.rs and the other plugins provide
+        .insert_resource(gameconfig)
+        .insert_resource(Keybinds::default())
+        .insert_resource(MusicVolume { volume: 0.0 })
+        .init_resource::<Score>()
+        .init_resource::<PickupInventory>()
+        .insert_state(GameState::Game)
+        .init_state::<MenuState>()
+        .init_state::<ControllerState>()
+        .add_plugins((
+            physics::PhysicsPlugin,
+            asteroids::AsteroidPlugin,
+            player::PlayerPlugin,
+            game_over::GameOverPlugin,
+            floating_origin::plugin,
+            powerups::plugin,
+            shop::plugin,
+        ));
+        app.finish();
+        app.cleanup();
+        app.update();
+
+        Self { app }
+    }
+
+    pub fn config(&self) -> &GameConfig {
+        self.app.world().resource::<GameConfig>()
+    }
+
+    pub fn world(&mut self) -> &mut World {
+        self.app.world_mut()
+    }
+
+    /// Ship with the hitboxes of the manifest, without its model and cameras
+    pub fn spawn_ship(&mut self, position: Vec3) -> Entity {
+        let health = ShipHealth::new(&self.config().ship.damage);
+        let material = PhysicsMaterial::from(&self.config().ship.material);
+        let shapes = self.config().ship.hitboxes.shapes.clone();
+        let ship = self
+            .world()
+            .spawn((
+                Player,
+                health,
+                material,
+                Velocity(Vec3::ZERO),
+                RotationVelocity(Vec3::ZERO),
+                Transform::from_translation(position),
+            ))
+            .id();
+        for shape in &shapes {
+            self.world().spawn((hitbox(shape), ChildOf(ship)));
+        }
+        ship
+    }
+
+    pub fn spawn_asteroid(&mut self, size: f32, position: Vec3, velocity: Vec3) -> Entity {
+        let material = asteroids::material(self.config(), asteroids::size_type(size));
+        self.world()
+            .spawn((
+                Asteroid { size },
+                material,
+                Transform::from_translation(position).with_scale(Vec3::splat(size)),
+                Velocity(velocity),
+                RotationVelocity(Vec3::ZERO),
+            ))
+            .id()
+    }
+
+    pub fn step(&mut self, frames: usize) {
+        for _ in 0..frames {
+            self.app.update();
+        }
+    }
+
+    pub fn step_secs(&mut self, seconds: f32) {
+        self.step((seconds / FRAME).ceil() as usize);
+    }
+
+    pub fn state<S: States>(&self) -> S {
+        self.app.world().resource::<State<S>>().get().clone()
+    }
+
+    /// Total volume of the asteroids, in size³
+    pub fn asteroid_volume(&mut self) -> f32 {
+        let mut query = self.world().query::<&Asteroid>();
+        query
+            .iter(self.app.world())
+            .map(|asteroid| asteroid.size.powi(3))
+            .sum()
+    }
+}
+
+/// Rock of `size_type` and size 1 shot by the player at the origin, tests override the fields they care about
+pub fn destroyed(size_type: &'static str) -> AsteroidDestroyed {
+    AsteroidDestroyed {
+        asteroid: Entity::PLACEHOLDER,
+        position: Vec3::ZERO,
+        velocity: Vec3::ZERO,
+        impulse: Vec3::Z,
+        size: 1.0,
+        size_type,
+        by_player: true,
+        mined: false,
+    }
+}
+
+/// Hit of `impulse` along X on `ship`
+pub fn impact(ship: Entity, impulse: f32) -> ShipImpact {
+    ShipImpact {
+        ship,
+        asteroid: Entity::PLACEHOLDER,
+        position: Vec3::ZERO,
+        impulse: Vec3::X * impulse,
+        size_type: "M",
+    }
+}
@@ -0,0 +1,49 @@
+use bevy::prelude::*;
+
+use super::TestApp;
+use crate::asteroids::Sun;
+use crate::floating_origin::{FloatingOrigin, Voyage};
+
+#[test]
+fn far_ship_recentres_the_world() {
+    let mut app = TestApp::new();
+    let start = Vec3::new(app.config().physics.recenter_distance * 1.5, 0.0, 0.0);
+    let ship = app.spawn_ship(start);
+    let asteroid = app.spawn_asteroid(3.0, start + Vec3::Z * 20.0, Vec3::ZERO);
+    let mut suns = app.world().query_filtered::<&Transform, With<Sun>>();
+    let sun_before = suns.single(app.app.world()).unwrap().translation;
+
+    app.step(1);
+
+    let ship_position = app.world().get::<Transform>(ship).unwrap().translation;
+    let asteroid_position = app.world().get::<Transform>(asteroid).unwrap().translation;
+    let offset = app.world().resource::<FloatingOrigin>().offset.as_vec3();
+    assert!(ship_position.length() < 1.0, "ship at {ship_position}");
+    assert!(offset.distance(start) < 1.0, "offset {offset}");
+    // relative layout unchanged
+    assert!((asteroid_position - ship_position).distance(Vec3::Z * 20.0) < 0.1);
+    let sun_after = suns.single(app.app.world()).unwrap().translation;
+    assert!(sun_after.distance(sun_before - offset) < 0.1);
+
+    // close to the new origin, nothing moves anymore
+    app.step(1);
+    let offset_after = app.world().resource::<FloatingOrigin>().offset.as_vec3();
+    assert_eq!(offset, offset_after);
+}
+
+#[test]
+fn voyage_adds_up_across_recentres() {
+    let mut app = TestApp::new();
+    let recenter = app.config().physics.recenter_distance;
+    let ship = app.spawn_ship(Vec3::ZERO);
+
+    app.world().get_mut::<Transform>(ship).unwrap().translation = Vec3::X * recenter * 1.5;
+    app.step(1);
+    app.world().get_mut::<Transform>(ship).unwrap().translation = Vec3::X * recenter * 0.5;
+    app.step(1);
+
+    let offset = app.world().resource::<FloatingOrigin>().offset;
+    assert!(offset.length() > 0.0);
+    let farthest = app.world().resource::<Voyage>().farthest;
+    assert!((farthest - recenter as f64 * 2.0).abs() < 1.0, "{farthest}");
+}
@@ -0,0 +1,252 @@
+use std::time::Instant;
+
+use bevy::prelude::*;
+
+use super::TestApp;
+use crate::asteroids::messages::AsteroidDestroyed;
+use crate::asteroids::AsteroidDamage;
+use crate::globals_structs::Score;
+use crate::physics::{Parked, Velocity};
+use crate::player::beam::Beam;
+use crate::player::missiles::Missile;
+use crate::player::pool::ProjectilePool;
+use crate::player::weapons::{Arsenal, FirePattern, GunHeat, ProjectileKind};
+use crate::player::Ammo;
+
+#[test]
+fn holding_fire_overheats_the_guns() {
+    let mut app = TestApp::new();
+    app.spawn_ship(Vec3::ZERO);
+    // overheats well before the magazine is empty
+    app.world().resource_mut::<Arsenal>().weapons[0].heat = 30.0;
+    app.world()
+        .resource_mut::<ButtonInput<MouseButton>>()
+        .press(MouseButton::Left);
+
+    app.step(1);
+    let heat = app.world().resource::<GunHeat>().value;
+    assert!(heat > 0.0, "heat {heat}");
+    assert!(app.world().resource::<Arsenal>().cooldown > 0.0);
+
+    // heats faster than it cools
+    app.step_secs(2.0);
+    assert!(app.world().resource::<GunHeat>().overheated);
+
+    app.world()
+        .resource_mut::<ButtonInput<MouseButton>>()
+        .release(MouseButton::Left);
+    app.step_secs(5.0);
+    assert!(!app.world().resource::<GunHeat>().overheated);
+}
+
+#[test]
+fn empty_magazine_reloads_from_the_reserve() {
+    let mut app = TestApp::new();
+    app.spawn_ship(Vec3::ZERO);
+    let (magazine, reserve, reload_time) = {
+        let mut arsenal = app.world().resource_mut::<Arsenal>();
+        let weapon = arsenal.weapon_mut();
+        weapon.rounds = 1;
+        (weapon.magazine, weapon.reserve, weapon.reload_time)
+    };
+    app.world()
+        .resource_mut::<ButtonInput<MouseButton>>()
+        .press(MouseButton::Left);
+
+    app.step(2);
+    assert!(app.world().resource::<Arsenal>().reload.is_some());
+
+    app.step_secs(reload_time);
+    let arsenal = app.world().resource::<Arsenal>();
+    assert!(arsenal.reload.is_none());
+    // the held trigger keeps firing from the new magazine
+    assert!(arsenal.weapon().rounds > 0 && arsenal.weapon().rounds <= magazine);
+    assert_eq!(arsenal.weapon().reserve, reserve - magazine);
+}
+
+#[test]
+fn missile_steers_into_its_target() {
+    let mut app = TestApp::new();
+    app.spawn_ship(Vec3::new(0.0, 0.0, 20.0));
+    let rock = app.spawn_asteroid(3.0, Vec3::new(30.0, 0.0, -30.0), Vec3::ZERO);
+    app.world().spawn((
+        Ammo {
+            damage: 60.0,
+            radius: 1.2,
+            in_flight: true,
+        },
+        Missile { target: Some(rock) },
+        Transform::default(),
+        Velocity(Vec3::NEG_Z * 30.0),
+    ));
+
+    // straight ahead it would miss by 30 units
+    app.step_secs(2.0);
+    // the rock may already be done with its despawn animation
+    assert!(app.world().resource::<Score>().value > 0);
+}
+
+#[test]
+fn held_beam_mines_the_asteroid_ahead() {
+    let mut app = TestApp::new();
+    app.spawn_ship(Vec3::ZERO);
+    let rock = app.spawn_asteroid(3.0, Vec3::new(0.0, 0.0, -20.0), Vec3::ZERO);
+    {
+        let mut arsenal = app.world().resource_mut::<Arsenal>();
+        arsenal.current = arsenal
+            .weapons
+            .iter()
+            .position(|weapon| weapon.projectile == ProjectileKind::Beam)
+            .expect("the manifest has a beam weapon");
+    }
+    app.world()
+        .resource_mut::<ButtonInput<MouseButton>>()
+        .press(MouseButton::Left);
+
+    app.step(1);
+    let beam = app.world().resource::<Beam>();
+    assert!(beam.rays.iter().all(|ray| ray.hit == Some(rock)));
+    // no projectile, the pool stays idle
+    assert_eq!(app.world().resource::<ProjectilePool>().lit, 0);
+
+    app.step_secs(1.0);
+    assert!(app.world().resource::<Score>().value > 0);
+    assert!(app.world().resource::<GunHeat>().value > 0.0);
+
+    app.world()
+        .resource_mut::<ButtonInput<MouseButton>>()
+        .release(MouseButton::Left);
+    app.step(1);
+    assert!(app.world().resource::<Beam>().rays.is_empty());
+}
+
+#[test]
+fn converging_rays_break_a_rock_once() {
+    let mut app = TestApp::new();
+    app.spawn_ship(Vec3::ZERO);
+    let rock = app.spawn_asteroid(3.0, Vec3::new(0.0, 0.0, -20.0), Vec3::ZERO);
+    // the first ray of the next frame breaks it, the second one finds it still there
+    let hit_points = 3.0 * app.config().asteroids.hit_points;
+    app.world()
+        .entity_mut(rock)
+        .insert(AsteroidDamage(hit_points - 0.01));
+    {
+        let mut arsenal = app.world().resource_mut::<Arsenal>();
+        arsenal.current = arsenal
+            .weapons
+            .iter()
+            .position(|weapon| weapon.projectile == ProjectileKind::Beam)
+            .expect("the manifest has a beam weapon");
+        assert_eq!(arsenal.weapon().pattern, FirePattern::Simultaneous);
+    }
+    let mut cursor = app
+        .world()
+        .resource::<Messages<AsteroidDestroyed>>()
+        .get_cursor();
+    app.world()
+        .resource_mut::<ButtonInput<MouseButton>>()
+        .press(MouseButton::Left);
+
+    let mut broken = 0;
+    for _ in 0..10 {
+        app.step(1);
+        let messages = app.world().resource::<Messages<AsteroidDestroyed>>();
+        broken += cursor
+            .read(messages)
+            .filter(|message| message.asteroid == rock)
+            .count();
+    }
+    assert_eq!(broken, 1);
+}
+
+#[test]
+fn spent_projectiles_return_to_the_pool() {
+    let mut app = TestApp::new();
+    app.spawn_ship(Vec3::ZERO);
+    let salvo = |app: &mut TestApp| {
+        let mut mouse = app.world().resource_mut::<ButtonInput<MouseButton>>();
+        mouse.press(MouseButton::Left);
+        app.step_secs(0.5);
+        let mut mouse = app.world().resource_mut::<ButtonInput<MouseButton>>();
+        mouse.release(MouseButton::Left);
+        // out of range by then
+        app.step_secs(3.0);
+    };
+
+    salvo(&mut app);
+    let size = app.world().resource::<ProjectilePool>().size;
+    assert_eq!(size, app.config().ship.ammo.pool_size);
+    assert_eq!(app.world().resource::<ProjectilePool>().lit, 0);
+
+    salvo(&mut app);
+    assert_eq!(app.world().resource::<ProjectilePool>().size, size);
+}
+
+#[test]
+fn parked_projectiles_stay_out_of_the_simulation() {
+    let mut app = TestApp::new();
+    app.spawn_ship(Vec3::ZERO);
+    app.world()
+        .resource_mut::<ButtonInput<MouseButton>>()
+        .press(MouseButton::Left);
+    app.step_secs(0.5);
+    app.world()
+        .resource_mut::<ButtonInput<MouseButton>>()
+        .release(MouseButton::Left);
+    // out of range by then
+    app.step_secs(3.0);
+
+    let mut parked = app
+        .world()
+        .query_filtered::<(Entity, &Transform, &Velocity), With<Parked>>();
+    let before: Vec<(Entity, Vec3)> = parked
+        .iter(app.app.world())
+        .map(|(entity, transform, _)| (entity, transform.translation))
+        .collect();
+    assert_eq!(before.len(), app.config().ship.ammo.pool_size);
+
+    // the sun keeps pulling on everything else
+    app.step_secs(2.0);
+    for (entity, translation) in before {
+        let (_, transform, velocity) = parked.get(app.app.world(), entity).unwrap();
+        // only the interpolation rounding moves them
+        assert!(transform.translation.distance(translation) < 1e-3);
+        assert_eq!(**velocity, Vec3::ZERO);
+    }
+}
+
+/// Shots per second the whole gameplay update sustains, run with
+/// `cargo test projectile_throughput -- --ignored --nocapture`
+#[test]
+#[ignore]
+fn projectile_throughput() {
+    let mut app = TestApp::new();
+    app.spawn_ship(Vec3::ZERO);
+    let shots_per_frame = 16;
+    {
+        let mut arsenal = app.world().resource_mut::<Arsenal>();
+        let weapon = arsenal.weapon_mut();
+        weapon.pattern = FirePattern::Spread;
+        weapon.spread_count = shots_per_frame;
+        weapon.fire_rate = 1000.0;
+        weapon.heat = 0.0;
+        weapon.rounds = usize::MAX;
+    }
+    app.world()
+        .resource_mut::<ButtonInput<MouseButton>>()
+        .press(MouseButton::Left);
+
+    let frames = 600;
+    let start = Instant::now();
+    app.step(frames);
+    let elapsed = start.elapsed().as_secs_f32();
+
+    let pool = app.world().resource::<ProjectilePool>();
+    println!(
+        "{} shots in {elapsed:.2}s, {:.0} shots/s, pool of {} projectiles, {} lit",
+        frames * shots_per_frame,
+        (frames * shots_per_frame) as f32 / elapsed,
+        pool.size,
+        pool.lit
+    );
+}